@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{
+    Controller, Input, Output, PidController, Reactor, Simulation, ThresholdController,
+    DEFAULT_TICK_RATE,
+};
+
+const TIME_DELTA: f32 = 1.0 / DEFAULT_TICK_RATE;
+
+/// Ticks given to the reactor to settle before and after a step.
+const SETTLE_TICKS: u32 = 120 * 60;
+
+#[derive(Debug, Clone, Copy)]
+pub struct OperatingPoint {
+    fission_rate: f32,
+    turbine_rate: f32,
+}
+
+impl OperatingPoint {
+    pub fn new(fission_rate: f32, turbine_rate: f32) -> Self {
+        Self {
+            fission_rate,
+            turbine_rate,
+        }
+    }
+
+    pub fn get_fission_rate(&self) -> f32 {
+        self.fission_rate
+    }
+
+    pub fn get_turbine_rate(&self) -> f32 {
+        self.turbine_rate
+    }
+}
+
+impl Reactor {
+    /// Open-loop time constant in seconds at the operating point, measured as
+    /// the time to reach 63% of the temperature change after a small fission
+    /// step. Runs on a fresh reactor with the same configuration. None when
+    /// the step doesn't move the temperature or the reactor melts down.
+    pub fn time_constant(&self, operating_point: OperatingPoint) -> Option<f32> {
+        let mut reactor = self.probe();
+        reactor.set_fission_rate(operating_point.fission_rate);
+        reactor.set_turbine_rate(operating_point.turbine_rate);
+        for _ in 0..SETTLE_TICKS {
+            reactor.update(TIME_DELTA);
+        }
+
+        let initial = reactor.get_temperature();
+        let step = if operating_point.fission_rate + 5.0 > 100.0 {
+            -5.0
+        } else {
+            5.0
+        };
+        reactor.set_fission_rate(operating_point.fission_rate + step);
+
+        let response: Vec<f32> = (0..SETTLE_TICKS)
+            .map(|_| {
+                reactor.update(TIME_DELTA);
+                (reactor.get_temperature() - initial).abs()
+            })
+            .collect();
+
+        let change = response.last().copied().unwrap_or_default();
+        if change <= f32::EPSILON || reactor.is_melted_down() {
+            return None;
+        }
+
+        let tick = response.iter().position(|delta| *delta >= 0.63 * change)?;
+        Some((tick + 1) as f32 * TIME_DELTA)
+    }
+}
+
+/// Records the temperature every tick.
+struct TemperatureTrace(Vec<f32>);
+
+impl Controller for TemperatureTrace {
+    fn update(&mut self, _dt: f32, output: &Output, _input: &mut Input) {
+        self.0.push(output.get_temperature());
+    }
+}
+
+/// PID gains suggested by a relay feedback experiment.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AutotuneResult {
+    kp: f32,
+    ki: f32,
+    kd: f32,
+    ultimate_gain: f32,
+    /// Oscillation period in seconds.
+    period: f32,
+}
+
+impl AutotuneResult {
+    pub fn gains(&self) -> (f32, f32, f32) {
+        (self.kp, self.ki, self.kd)
+    }
+
+    pub fn get_ultimate_gain(&self) -> f32 {
+        self.ultimate_gain
+    }
+
+    pub fn get_period(&self) -> f32 {
+        self.period
+    }
+
+    pub fn controller(&self) -> PidController {
+        PidController::new(self.kp, self.ki, self.kd)
+    }
+}
+
+/// Ziegler-Nichols tuning from a relay (bang-bang) experiment around
+/// `setpoint`. Returns `None` if the reactor never oscillates.
+pub fn autotune(reactor: Reactor, setpoint: f32, duration: Duration) -> Option<AutotuneResult> {
+    let (on, off) = (100.0, 0.0);
+    let relay = ThresholdController::new(setpoint, setpoint, on, off);
+    let simulation = Simulation::new(duration, reactor, (relay, TemperatureTrace(Vec::new())));
+    let dt = simulation.get_time_delta();
+    let (_, TemperatureTrace(trace)) = simulation.run();
+
+    // Skip the initial warm up
+    let settled = &trace[trace.len() / 2..];
+    let crossings: Vec<usize> = settled
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0] < setpoint && pair[1] >= setpoint)
+        .map(|(tick, _)| tick)
+        .collect();
+    if crossings.len() < 2 {
+        return None;
+    }
+
+    let period = (crossings[crossings.len() - 1] - crossings[0]) as f32
+        / (crossings.len() - 1) as f32
+        * dt;
+    let max = settled.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let min = settled.iter().copied().fold(f32::INFINITY, f32::min);
+    let amplitude = (max - min) / 2.0;
+    if amplitude <= 0.0 || period <= 0.0 {
+        return None;
+    }
+
+    let ultimate_gain = 4.0 * (on - off) / 2.0 / (std::f32::consts::PI * amplitude);
+    let kp = 0.6 * ultimate_gain;
+    Some(AutotuneResult {
+        kp,
+        ki: 2.0 * kp / period,
+        kd: kp * period / 8.0,
+        ultimate_gain,
+        period,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_constant_of_a_stable_point() {
+        let reactor = Reactor::new(320.0, 4000.0);
+        let time_constant = reactor.time_constant(OperatingPoint::new(10.0, 50.0));
+        assert!(matches!(time_constant, Some(tau) if tau > 1.0 && tau < 5.0));
+    }
+
+    #[test]
+    fn time_constant_of_a_degenerate_point() {
+        let reactor = Reactor::new(320.0, 4000.0);
+        // Melts down before the step
+        assert_eq!(reactor.time_constant(OperatingPoint::new(98.0, 100.0)), None);
+        // Melts down after the step
+        assert_eq!(reactor.time_constant(OperatingPoint::new(20.0, 50.0)), None);
+    }
+}
@@ -0,0 +1,137 @@
+use std::{fmt::Display, time::Duration};
+
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::{Controller, Input, Output, Reactor, RunReport, Simulation};
+
+/// Tracks the highest temperature seen during a run.
+struct PeakTemperature(f32);
+
+impl Controller for PeakTemperature {
+    fn update(&mut self, _dt: f32, output: &Output, _input: &mut Input) {
+        self.0 = self.0.max(output.get_temperature());
+    }
+}
+
+pub struct SweepPoint<P, C> {
+    params: P,
+    controller: C,
+    max_temperature: f32,
+    critical_temperature: f32,
+}
+
+impl<P, C> SweepPoint<P, C> {
+    pub fn get_params(&self) -> &P {
+        &self.params
+    }
+
+    pub fn get_controller(&self) -> &C {
+        &self.controller
+    }
+
+    pub fn get_max_temperature(&self) -> f32 {
+        self.max_temperature
+    }
+
+    pub fn is_safe(&self) -> bool {
+        self.max_temperature <= self.critical_temperature
+    }
+}
+
+/// Aggregate over every configuration of a sweep.
+pub struct SweepReport<P, C> {
+    points: Vec<SweepPoint<P, C>>,
+}
+
+impl<P, C> SweepReport<P, C> {
+    pub fn points(&self) -> &[SweepPoint<P, C>] {
+        &self.points
+    }
+
+    pub fn into_points(self) -> Vec<SweepPoint<P, C>> {
+        self.points
+    }
+
+    pub fn safe_count(&self) -> usize {
+        self.points.iter().filter(|point| point.is_safe()).count()
+    }
+
+    pub fn unsafe_count(&self) -> usize {
+        self.points.len() - self.safe_count()
+    }
+
+    /// The configuration reaching the highest temperature.
+    pub fn worst(&self) -> Option<&SweepPoint<P, C>> {
+        self.points
+            .iter()
+            .max_by(|a, b| a.max_temperature.total_cmp(&b.max_temperature))
+    }
+}
+
+impl<P, C> Display for SweepReport<P, C>
+where
+    P: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} configurations safe",
+            self.safe_count(),
+            self.points.len()
+        )?;
+        if let Some(worst) = self.worst() {
+            write!(
+                f,
+                ", worst max_temp {} with {:?}",
+                worst.max_temperature, worst.params
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs a simulation for every parameter in parallel, `build` constructs the
+/// reactor and controller for a parameter.
+pub fn run_sweep<P, C, F>(params: &[P], duration: Duration, build: F) -> SweepReport<P, C>
+where
+    P: Clone + Send + Sync,
+    C: Controller + Send,
+    F: Fn(&P) -> (Reactor, C) + Sync,
+{
+    let points = params
+        .par_iter()
+        .map(|params| {
+            let (reactor, controller) = build(params);
+            let critical_temperature = reactor.get_safety_limits().get_critical_temperature();
+            let simulation =
+                Simulation::new(duration, reactor, (controller, PeakTemperature(0.0)));
+            let (controller, PeakTemperature(max_temperature)) = simulation.run();
+            SweepPoint {
+                params: params.clone(),
+                controller,
+                max_temperature,
+                critical_temperature,
+            }
+        })
+        .collect();
+
+    SweepReport { points }
+}
+
+/// Like `run_sweep`, but only collects the `RunReport` of every parameter, in
+/// the order of `params`.
+pub fn sweep<P, C, F>(params: &[P], duration: Duration, build: F) -> Vec<RunReport>
+where
+    P: Sync,
+    C: Controller,
+    F: Fn(&P) -> (Reactor, C) + Sync,
+{
+    params
+        .par_iter()
+        .map(|params| {
+            let (reactor, controller) = build(params);
+            let (_, report) = Simulation::new(duration, reactor, controller).run_with_report();
+            report
+        })
+        .collect()
+}
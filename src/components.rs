@@ -0,0 +1,153 @@
+use crate::Integrator;
+
+/// Slew rate of the core and turbine targets, per second.
+pub const DEFAULT_SLEW_RATE: f32 = 5.0;
+/// Time constant of the core and turbine lag, in seconds.
+pub const DEFAULT_TIME_CONSTANT: f32 = 1.0;
+
+/// Fission side of the reactor. The target slews towards the commanded
+/// fission rate and the value lags behind the target.
+#[derive(Debug, Clone)]
+pub struct Core {
+    value: f32,
+    target: f32,
+    /// Rate the target follows the command at, per second.
+    slew_rate: f32,
+    /// Seconds the value takes to close 63% of the gap to the target.
+    time_constant: f32,
+}
+
+impl Core {
+    pub fn new() -> Self {
+        Self {
+            value: 0.0,
+            target: 0.0,
+            slew_rate: DEFAULT_SLEW_RATE,
+            time_constant: DEFAULT_TIME_CONSTANT,
+        }
+    }
+
+    pub fn with_response(mut self, slew_rate: f32, time_constant: f32) -> Self {
+        self.slew_rate = slew_rate;
+        self.time_constant = time_constant.max(f32::EPSILON);
+        self
+    }
+
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn get_target(&self) -> f32 {
+        self.target
+    }
+
+    pub fn get_slew_rate(&self) -> f32 {
+        self.slew_rate
+    }
+
+    pub fn get_time_constant(&self) -> f32 {
+        self.time_constant
+    }
+
+    pub(crate) fn set_state(&mut self, value: f32, target: f32) {
+        self.value = value;
+        self.target = target;
+    }
+
+    /// Slews towards `new_target`, but never past the `ceiling` the fuel can
+    /// sustain, so the target is always achievable.
+    pub fn update(
+        &mut self,
+        new_target: f32,
+        ceiling: f32,
+        time_delta: f32,
+        integrator: &impl Integrator,
+    ) {
+        let new_target = new_target.min(ceiling);
+        self.target = if self.target >= new_target {
+            (self.target - time_delta * self.slew_rate).max(new_target)
+        } else {
+            (self.target + time_delta * self.slew_rate).min(new_target)
+        };
+        self.target = self.target.min(ceiling);
+        let target = self.target;
+        let time_constant = self.time_constant;
+        self.value = integrator.step(self.value, time_delta, &|value| {
+            (target - value) / time_constant
+        });
+        self.value = self.value.clamp(0.0, 100.0);
+    }
+}
+
+impl Default for Core {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Turbine side of the reactor, responds like the `Core` without a ceiling.
+#[derive(Debug, Clone)]
+pub struct Turbine {
+    value: f32,
+    target: f32,
+    slew_rate: f32,
+    time_constant: f32,
+}
+
+impl Turbine {
+    pub fn new() -> Self {
+        Self {
+            value: 0.0,
+            target: 0.0,
+            slew_rate: DEFAULT_SLEW_RATE,
+            time_constant: DEFAULT_TIME_CONSTANT,
+        }
+    }
+
+    pub fn with_response(mut self, slew_rate: f32, time_constant: f32) -> Self {
+        self.slew_rate = slew_rate;
+        self.time_constant = time_constant.max(f32::EPSILON);
+        self
+    }
+
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn get_target(&self) -> f32 {
+        self.target
+    }
+
+    pub fn get_slew_rate(&self) -> f32 {
+        self.slew_rate
+    }
+
+    pub fn get_time_constant(&self) -> f32 {
+        self.time_constant
+    }
+
+    pub(crate) fn set_state(&mut self, value: f32, target: f32) {
+        self.value = value;
+        self.target = target;
+    }
+
+    pub fn update(&mut self, new_target: f32, time_delta: f32, integrator: &impl Integrator) {
+        self.target = if self.target >= new_target {
+            (self.target - time_delta * self.slew_rate).max(new_target)
+        } else {
+            (self.target + time_delta * self.slew_rate).min(new_target)
+        };
+        let target = self.target;
+        let time_constant = self.time_constant;
+        self.value = integrator.step(self.value, time_delta, &|value| {
+            (target - value) / time_constant
+        });
+        self.value = self.value.clamp(0.0, 100.0);
+    }
+}
+
+impl Default for Turbine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,16 +1,115 @@
-use crate::{Input, Output};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    FuelRod, Input, Output, Reactor, DEFAULT_TEMPERATURE_RATE, SETPOINT_DEFAULT,
+    UNSAFE_TEMPERATURE,
+};
 
 impl<C> Controller for &mut C
 where
     C: Controller,
 {
-    fn update(&mut self, output: &Output, input: &mut Input) {
-        (*self).update(output, input);
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        (*self).update(dt, output, input);
+    }
+
+    fn reset(&mut self) {
+        (*self).reset();
+    }
+
+    fn finish(&mut self) {
+        (*self).finish();
+    }
+}
+
+impl<C> Controller for Box<C>
+where
+    C: Controller + ?Sized,
+{
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        (**self).update(dt, output, input);
+    }
+
+    fn reset(&mut self) {
+        (**self).reset();
+    }
+
+    fn finish(&mut self) {
+        (**self).finish();
+    }
+}
+
+impl<C> Controller for Vec<C>
+where
+    C: Controller,
+{
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        for controller in self {
+            controller.update(dt, output, input);
+        }
+    }
+
+    fn reset(&mut self) {
+        for controller in self {
+            controller.reset();
+        }
+    }
+
+    fn finish(&mut self) {
+        for controller in self {
+            controller.finish();
+        }
     }
 }
 
 pub trait Controller {
-    fn update(&mut self, output: &Output, input: &mut Input);
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input);
+
+    /// Clears any state carried between ticks, so the controller can be
+    /// reused for another run.
+    fn reset(&mut self) {}
+
+    /// Called once after the last tick of a run, to flush or summarize.
+    fn finish(&mut self) {}
+}
+
+/// Controllers assembled at runtime, updated in the order they were pushed.
+#[derive(Default)]
+pub struct ControllerChain {
+    controllers: Vec<Box<dyn Controller>>,
+}
+
+impl ControllerChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(mut self, controller: Box<dyn Controller>) -> Self {
+        self.controllers.push(controller);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.controllers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.controllers.is_empty()
+    }
+}
+
+impl Controller for ControllerChain {
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        self.controllers.update(dt, output, input);
+    }
+
+    fn reset(&mut self) {
+        self.controllers.reset();
+    }
+
+    fn finish(&mut self) {
+        self.controllers.finish();
+    }
 }
 
 macro_rules! impl_controller_tupple {
@@ -19,20 +118,1199 @@ macro_rules! impl_controller_tupple {
         where
             $($T: Controller,)*
         {
-            fn update(&mut self, output: &Output, input: &mut Input) {
-                $(self.$idx.update(output, input);)*
+            fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+                $(self.$idx.update(dt, output, input);)*
+            }
+
+            fn reset(&mut self) {
+                $(self.$idx.reset();)*
+            }
+
+            fn finish(&mut self) {
+                $(self.$idx.finish();)*
             }
         }
     };
 }
 
 impl Controller for () {
-    fn update(&mut self, _output: &Output, _input: &mut Input) {}
+    fn update(&mut self, _dt: f32, _output: &Output, _input: &mut Input) {}
 }
 
 impl_controller_tupple!(0 A);
 impl_controller_tupple!(0 A, 1 B);
 impl_controller_tupple!(0 A, 1 B, 2 C);
 impl_controller_tupple!(0 A, 1 B, 2 C, 3 D);
-impl_controller_tupple!(0 A, 1 Bf, 2 C, 3 D, 4 E);
+impl_controller_tupple!(0 A, 1 B, 2 C, 3 D, 4 E);
 impl_controller_tupple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F);
+impl_controller_tupple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G);
+impl_controller_tupple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H);
+impl_controller_tupple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I);
+impl_controller_tupple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J);
+
+// Larger tuples must keep implementing `Controller`.
+const _: fn() = || {
+    fn assert_controller<C: Controller>() {}
+    assert_controller::<((), (), (), (), (), (), (), ())>();
+};
+
+/// Keeps fission at or above `min_fission`, backing off towards it as the
+/// temperature approaches `max_temp`.
+#[derive(Clone)]
+pub struct FloorCeilingController {
+    min_fission: f32,
+    max_temp: f32,
+    gain: f32,
+}
+
+impl FloorCeilingController {
+    pub fn new(min_fission: f32, max_temp: f32) -> Self {
+        Self {
+            min_fission,
+            max_temp,
+            gain: 0.05,
+        }
+    }
+
+    /// Fission added per degree below `max_temp`.
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+}
+
+impl Controller for FloorCeilingController {
+    fn update(&mut self, _dt: f32, output: &Output, input: &mut Input) {
+        let headroom = self.max_temp - output.get_temperature();
+        let fission = self.min_fission + headroom * self.gain;
+        input.set_fission_rate(fission.max(self.min_fission));
+    }
+}
+
+/// PID on a temperature estimate from a constant-rate Kalman filter, for
+/// use with noisy temperature readings.
+#[derive(Clone)]
+pub struct KalmanController {
+    kp: f32,
+    ki: f32,
+    kd: f32,
+    setpoint: f32,
+    integral: f32,
+    process_noise: f32,
+    measurement_noise: f32,
+    /// Estimated temperature and its rate per second.
+    estimate: [f32; 2],
+    covariance: [[f32; 2]; 2],
+    initialized: bool,
+}
+
+impl KalmanController {
+    pub fn new(kp: f32, ki: f32, kd: f32, setpoint: f32) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            setpoint,
+            integral: 0.0,
+            process_noise: 100_000.0,
+            measurement_noise: 100.0,
+            estimate: [0.0; 2],
+            covariance: [[0.0; 2]; 2],
+            initialized: false,
+        }
+    }
+
+    /// `process_noise` is the variance of the temperature acceleration,
+    /// `measurement_noise` the variance of a temperature reading.
+    pub fn with_noise(mut self, process_noise: f32, measurement_noise: f32) -> Self {
+        self.process_noise = process_noise;
+        self.measurement_noise = measurement_noise;
+        self
+    }
+
+    pub fn get_estimated_temperature(&self) -> f32 {
+        self.estimate[0]
+    }
+
+    pub fn get_estimated_rate(&self) -> f32 {
+        self.estimate[1]
+    }
+
+    fn filter(&mut self, measurement: f32, dt: f32) {
+        if !self.initialized {
+            self.estimate = [measurement, 0.0];
+            self.covariance = [[self.measurement_noise, 0.0], [0.0, self.process_noise]];
+            self.initialized = true;
+            return;
+        }
+
+        // Predict
+        let [temperature, rate] = self.estimate;
+        let predicted = temperature + rate * dt;
+        let [[p00, p01], [p10, p11]] = self.covariance;
+        let q = self.process_noise;
+        let p00 = p00 + dt * (p10 + p01) + dt * dt * p11 + q * dt.powi(4) / 4.0;
+        let p01 = p01 + dt * p11 + q * dt.powi(3) / 2.0;
+        let p10 = p10 + dt * p11 + q * dt.powi(3) / 2.0;
+        let p11 = p11 + q * dt * dt;
+
+        // Update
+        let innovation = measurement - predicted;
+        let s = p00 + self.measurement_noise;
+        let k0 = p00 / s;
+        let k1 = p10 / s;
+        self.estimate = [predicted + k0 * innovation, rate + k1 * innovation];
+        self.covariance = [
+            [(1.0 - k0) * p00, (1.0 - k0) * p01],
+            [p10 - k1 * p00, p11 - k1 * p01],
+        ];
+    }
+}
+
+impl Controller for KalmanController {
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        self.filter(output.get_temperature(), dt);
+
+        let error = self.setpoint - self.get_estimated_temperature();
+        self.integral += error * dt;
+
+        let fission =
+            self.kp * error + self.ki * self.integral - self.kd * self.get_estimated_rate();
+        input.set_fission_rate(fission);
+    }
+
+    fn reset(&mut self) {
+        self.integral = 0.0;
+        self.estimate = [0.0; 2];
+        self.covariance = [[0.0; 2]; 2];
+        self.initialized = false;
+    }
+}
+
+/// Adds a small sinusoidal dither around the fission rate commanded by the
+/// inner controller, to average out nonlinearities in the core response.
+#[derive(Clone)]
+pub struct DitherController<C> {
+    inner: C,
+    amplitude: f32,
+    period_ticks: u64,
+    tick: u64,
+    /// Last command of the inner controller, without the dither.
+    base: Option<f32>,
+}
+
+impl<C> DitherController<C> {
+    pub fn new(inner: C, amplitude: f32, period_ticks: u64) -> Self {
+        Self {
+            inner,
+            amplitude,
+            period_ticks: period_ticks.max(1),
+            tick: 0,
+            base: None,
+        }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C> Controller for DitherController<C>
+where
+    C: Controller,
+{
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        // Take the previous dither out again, in case the inner controller
+        // keeps the last command instead of setting a new one
+        if let Some(base) = self.base {
+            input.set_fission_rate(base);
+        }
+        self.inner.update(dt, output, input);
+        let base = input.get_fission_rate();
+        self.base = Some(base);
+
+        self.tick = (self.tick + 1) % self.period_ticks;
+        let phase = self.tick as f32 / self.period_ticks as f32 * std::f32::consts::TAU;
+        input.set_fission_rate(base + self.amplitude * phase.sin());
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.tick = 0;
+        self.base = None;
+    }
+
+    fn finish(&mut self) {
+        self.inner.finish();
+    }
+}
+
+/// Lets `inner` propose the fission rate, but changes the command by at most
+/// `max_delta_per_sec` per second.
+#[derive(Clone)]
+pub struct RateLimited<C> {
+    inner: C,
+    max_delta_per_sec: f32,
+    previous: Option<f32>,
+}
+
+impl<C> RateLimited<C> {
+    pub fn new(inner: C, max_delta_per_sec: f32) -> Self {
+        Self {
+            inner,
+            max_delta_per_sec: max_delta_per_sec.max(0.0),
+            previous: None,
+        }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C> Controller for RateLimited<C>
+where
+    C: Controller,
+{
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        let previous = self.previous.unwrap_or(input.get_fission_rate());
+        self.inner.update(dt, output, input);
+
+        let max_delta = self.max_delta_per_sec * dt;
+        let fission_rate = input
+            .get_fission_rate()
+            .clamp(previous - max_delta, previous + max_delta);
+        input.set_fission_rate(fission_rate);
+        self.previous = Some(input.get_fission_rate());
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.previous = None;
+    }
+
+    fn finish(&mut self) {
+        self.inner.finish();
+    }
+}
+
+/// Runs `inner` only every `period_ticks` ticks, like a controller on a slower
+/// PLC. The reactor keeps the last command in between, and `inner` sees the
+/// time passed since its previous update.
+#[derive(Clone)]
+pub struct Sampled<C> {
+    inner: C,
+    period_ticks: u64,
+    tick: u64,
+    elapsed: f32,
+}
+
+impl<C> Sampled<C> {
+    pub fn new(inner: C, period_ticks: u64) -> Self {
+        Self {
+            inner,
+            period_ticks: period_ticks.max(1),
+            tick: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn get_period_ticks(&self) -> u64 {
+        self.period_ticks
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C> Controller for Sampled<C>
+where
+    C: Controller,
+{
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        let sample = self.tick.is_multiple_of(self.period_ticks);
+        self.tick += 1;
+        if !sample {
+            self.elapsed += dt;
+            return;
+        }
+
+        let dt = std::mem::take(&mut self.elapsed) + dt;
+        self.inner.update(dt, output, input);
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.tick = 0;
+        self.elapsed = 0.0;
+    }
+
+    fn finish(&mut self) {
+        self.inner.finish();
+    }
+}
+
+/// Delegates to `a` while `predicate` holds and to `b` otherwise. The
+/// controller taking over is reset first, so it starts from a clean state.
+///
+/// For example `SwitchController::new(pid, feedforward, outside_band(5000.0, 200.0))`
+/// runs the PID during transients and feedforward once the temperature settles.
+#[derive(Clone)]
+pub struct SwitchController<A, B, F> {
+    a: A,
+    b: B,
+    predicate: F,
+    active: Option<bool>,
+}
+
+impl<A, B, F> SwitchController<A, B, F>
+where
+    F: Fn(&Output) -> bool,
+{
+    pub fn new(a: A, b: B, predicate: F) -> Self {
+        Self {
+            a,
+            b,
+            predicate,
+            active: None,
+        }
+    }
+
+    /// Whether `a` drove the last tick, `None` before the first tick.
+    pub fn is_a_active(&self) -> Option<bool> {
+        self.active
+    }
+
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A, B, F> Controller for SwitchController<A, B, F>
+where
+    A: Controller,
+    B: Controller,
+    F: Fn(&Output) -> bool,
+{
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        let use_a = (self.predicate)(output);
+        if self.active.is_some_and(|active| active != use_a) {
+            if use_a {
+                self.a.reset();
+            } else {
+                self.b.reset();
+            }
+        }
+        self.active = Some(use_a);
+
+        if use_a {
+            self.a.update(dt, output, input);
+        } else {
+            self.b.update(dt, output, input);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.a.reset();
+        self.b.reset();
+        self.active = None;
+    }
+
+    fn finish(&mut self) {
+        self.a.finish();
+        self.b.finish();
+    }
+}
+
+/// Predicate for `SwitchController`, true while the temperature is more than
+/// `band` away from `setpoint`.
+pub fn outside_band(setpoint: f32, band: f32) -> impl Fn(&Output) -> bool {
+    move |output| (output.get_temperature() - setpoint).abs() > band
+}
+
+/// Projects the temperature forward from the net heat and the heating rate,
+/// flagging a breach of `threshold` before it happens.
+#[derive(Clone)]
+pub struct PredictiveSafetyMonitor {
+    threshold: f32,
+    heating_rate: f32,
+    temperature: f32,
+    net_heat: f32,
+    dt: f32,
+}
+
+impl PredictiveSafetyMonitor {
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            heating_rate: DEFAULT_TEMPERATURE_RATE,
+            temperature: 0.0,
+            net_heat: 0.0,
+            dt: 0.0,
+        }
+    }
+
+    /// Monitor using the effective heating rate of `reactor`.
+    pub fn for_reactor(threshold: f32, reactor: &Reactor) -> Self {
+        Self::new(threshold)
+            .with_heating_rate(reactor.get_heating_rate() / reactor.get_thermal_mass())
+    }
+
+    /// Must match the effective heating rate of the reactor, its heating rate
+    /// divided by its thermal mass.
+    pub fn with_heating_rate(mut self, heating_rate: f32) -> Self {
+        self.heating_rate = heating_rate;
+        self
+    }
+
+    /// Projected temperature `ticks` ticks ahead.
+    pub fn projected_temperature(&self, ticks: u64) -> f32 {
+        let max_change = self.heating_rate * ticks as f32 * self.dt;
+        self.temperature + (self.net_heat - self.temperature).clamp(-max_change, max_change)
+    }
+
+    pub fn will_breach_within(&self, ticks: u64) -> bool {
+        self.projected_temperature(ticks) > self.threshold
+    }
+}
+
+impl Controller for PredictiveSafetyMonitor {
+    fn update(&mut self, dt: f32, output: &Output, _input: &mut Input) {
+        self.temperature = output.get_temperature();
+        self.net_heat = output.get_net_heat();
+        self.dt = dt;
+    }
+
+    fn reset(&mut self) {
+        self.temperature = 0.0;
+        self.net_heat = 0.0;
+    }
+}
+
+/// The game's automatic control: turbine follows the load and fission
+/// follows the heat the turbine demands.
+#[derive(Clone)]
+pub struct AutoController;
+
+impl AutoController {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for AutoController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Controller for AutoController {
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        let turbine_rate = input.get_turbine_rate();
+        input.set_turbine_rate(turbine_rate + (input.get_load() - turbine_rate) * dt);
+
+        input.set_fission_rate(feedforward_fission(output));
+    }
+}
+
+/// PID driving the fission rate from the temperature error.
+#[derive(Clone)]
+pub struct PidController {
+    kp: f32,
+    ki: f32,
+    kd: f32,
+    setpoint: f32,
+    integral: f32,
+    prev_error: Option<f32>,
+    output_limits: (f32, f32),
+    integral_limits: (f32, f32),
+}
+
+impl PidController {
+    pub fn new(kp: f32, ki: f32, kd: f32) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            setpoint: SETPOINT_DEFAULT,
+            integral: 0.0,
+            prev_error: None,
+            output_limits: (0.0, 100.0),
+            integral_limits: (f32::NEG_INFINITY, f32::INFINITY),
+        }
+    }
+
+    pub fn set_output_limits(&mut self, min: f32, max: f32) {
+        self.output_limits = (min, max);
+    }
+
+    pub fn set_integral_limits(&mut self, min: f32, max: f32) {
+        self.integral_limits = (min, max);
+    }
+
+    pub fn set_gains(&mut self, kp: f32, ki: f32, kd: f32) {
+        (self.kp, self.ki, self.kd) = (finite_or_zero(kp), finite_or_zero(ki), finite_or_zero(kd));
+    }
+
+    pub fn get_gains(&self) -> (f32, f32, f32) {
+        (self.kp, self.ki, self.kd)
+    }
+
+    pub fn with_setpoint(mut self, setpoint: f32) -> Self {
+        self.setpoint = setpoint;
+        self
+    }
+
+    pub fn set_setpoint(&mut self, setpoint: f32) {
+        self.setpoint = finite_or_zero(setpoint);
+    }
+
+    pub fn get_setpoint(&self) -> f32 {
+        self.setpoint
+    }
+
+    pub fn get_integral(&self) -> f32 {
+        self.integral
+    }
+
+    /// Clears the integral and derivative history.
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.prev_error = None;
+    }
+
+    /// Advances the PID by `dt` seconds for the measured value. The integral
+    /// is held while the output is saturated in the direction it would grow.
+    pub fn compute(&mut self, measurement: f32, dt: f32) -> f32 {
+        let error = self.setpoint - measurement;
+        let derivative = match self.prev_error {
+            Some(prev_error) if dt > 0.0 => (error - prev_error) / dt,
+            _ => 0.0,
+        };
+        self.prev_error = Some(error);
+
+        let (integral_min, integral_max) = self.integral_limits;
+        let integral = (self.integral + error * dt).clamp(integral_min, integral_max);
+
+        let (output_min, output_max) = self.output_limits;
+        let unclamped = self.kp * error + self.ki * integral + self.kd * derivative;
+        let growth = (integral - self.integral) * self.ki;
+        let winding_up = (unclamped > output_max && growth > 0.0)
+            || (unclamped < output_min && growth < 0.0);
+        if !winding_up {
+            self.integral = integral;
+        }
+
+        (self.kp * error + self.ki * self.integral + self.kd * derivative)
+            .clamp(output_min, output_max)
+    }
+}
+
+impl Controller for PidController {
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        let fission = self.compute(output.get_temperature(), dt);
+        input.set_fission_rate(fission);
+    }
+
+    fn reset(&mut self) {
+        PidController::reset(self);
+    }
+}
+
+/// PID on the temperature with gains interpolated from a table keyed on the
+/// fuel potential, so one controller fits every fuel load.
+#[derive(Clone)]
+pub struct ScheduledPid {
+    /// (fuel_potential, kp, ki, kd), sorted by fuel potential.
+    schedule: Vec<(f32, f32, f32, f32)>,
+    pid: PidController,
+}
+
+impl ScheduledPid {
+    pub fn new(mut schedule: Vec<(f32, f32, f32, f32)>) -> Self {
+        schedule.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            schedule,
+            pid: PidController::new(0.0, 0.0, 0.0),
+        }
+    }
+
+    pub fn with_setpoint(mut self, setpoint: f32) -> Self {
+        self.pid.set_setpoint(setpoint);
+        self
+    }
+
+    pub fn get_schedule(&self) -> &[(f32, f32, f32, f32)] {
+        &self.schedule
+    }
+
+    /// Gains at `fuel_potential`, linear between the breakpoints and
+    /// extrapolated from the outer two beyond them.
+    pub fn gains_at(&self, fuel_potential: f32) -> (f32, f32, f32) {
+        let (a, b) = match self.schedule.as_slice() {
+            [] => return (0.0, 0.0, 0.0),
+            [only] => return (only.1, only.2, only.3),
+            schedule => {
+                let upper = schedule
+                    .iter()
+                    .position(|breakpoint| breakpoint.0 > fuel_potential)
+                    .unwrap_or(schedule.len() - 1)
+                    .clamp(1, schedule.len() - 1);
+                (schedule[upper - 1], schedule[upper])
+            }
+        };
+        let t = if b.0 > a.0 {
+            (fuel_potential - a.0) / (b.0 - a.0)
+        } else {
+            0.0
+        };
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+        (lerp(a.1, b.1), lerp(a.2, b.2), lerp(a.3, b.3))
+    }
+}
+
+impl Controller for ScheduledPid {
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        let (kp, ki, kd) = self.gains_at(output.get_fuel_potential());
+        self.pid.set_gains(kp, ki, kd);
+        let fission = self.pid.compute(output.get_temperature(), dt);
+        input.set_fission_rate(fission);
+    }
+
+    fn reset(&mut self) {
+        self.pid.reset();
+    }
+}
+
+/// Bang-bang control of the fission rate with a hysteresis band, switching
+/// on below `low` and off above `high`.
+#[derive(Clone)]
+pub struct ThresholdController {
+    high: f32,
+    low: f32,
+    on_value: f32,
+    off_value: f32,
+    on: bool,
+}
+
+impl ThresholdController {
+    pub fn new(high: f32, low: f32, on_value: f32, off_value: f32) -> Self {
+        Self {
+            high,
+            low,
+            on_value,
+            off_value,
+            on: true,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+}
+
+impl Controller for ThresholdController {
+    fn update(&mut self, _dt: f32, output: &Output, input: &mut Input) {
+        let temperature = output.get_temperature();
+        if temperature > self.high {
+            self.on = false;
+        } else if temperature < self.low {
+            self.on = true;
+        }
+
+        if self.on {
+            input.set_fission_rate(self.on_value);
+        } else {
+            input.set_fission_rate(self.off_value);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.on = true;
+    }
+}
+
+/// Emergency shutdown, forces the fission rate to 0 once the temperature
+/// exceeds `trip_temperature` and holds it there until the temperature drops
+/// below `reset_temperature`. Put it last in a tuple so it overrides the
+/// controllers before it.
+#[derive(Clone)]
+pub struct ScramController {
+    trip_temperature: f32,
+    reset_temperature: f32,
+    tripped: bool,
+}
+
+impl ScramController {
+    pub fn new(trip_temperature: f32, reset_temperature: f32) -> Self {
+        Self {
+            trip_temperature,
+            reset_temperature,
+            tripped: false,
+        }
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.tripped
+    }
+}
+
+impl Controller for ScramController {
+    fn update(&mut self, _dt: f32, output: &Output, input: &mut Input) {
+        let temperature = output.get_temperature();
+        if temperature > self.trip_temperature {
+            self.tripped = true;
+        } else if self.tripped && temperature < self.reset_temperature {
+            self.tripped = false;
+        }
+
+        if self.tripped {
+            input.set_fission_rate(0.0);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.tripped = false;
+    }
+}
+
+/// Swaps a fresh `fuel_rod` for the most depleted rod once the fuel remaining
+/// drops below `threshold`, `delay` seconds after noticing, like an operator
+/// walking over.
+#[derive(Clone)]
+pub struct FuelManagerController {
+    threshold: f32,
+    fuel_rod: FuelRod,
+    delay: f32,
+    waiting: Option<f32>,
+}
+
+impl FuelManagerController {
+    pub fn new(threshold: f32, fuel_rod: FuelRod) -> Self {
+        Self {
+            threshold,
+            fuel_rod,
+            delay: 0.0,
+            waiting: None,
+        }
+    }
+
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay.max(0.0);
+        self
+    }
+}
+
+impl Controller for FuelManagerController {
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        match &mut self.waiting {
+            Some(waited) => {
+                *waited += dt;
+                if *waited >= self.delay {
+                    input.insert_fuel_rod(self.fuel_rod);
+                    self.waiting = None;
+                }
+            }
+            None if output.get_fuel_remaining() < self.threshold => {
+                self.waiting = Some(0.0);
+            }
+            None => {}
+        }
+    }
+
+    fn reset(&mut self) {
+        self.waiting = None;
+    }
+}
+
+/// Like the `Input` setters, keeps a NaN from a broken calculation out of the
+/// controller state.
+fn finite_or_zero(value: f32) -> f32 {
+    if value.is_finite() {
+        value
+    } else {
+        0.0
+    }
+}
+
+/// Fission rate whose heat matches the heat demanded by the turbine.
+pub fn feedforward_fission(output: &Output) -> f32 {
+    fission_for_turbine(output.get_turbine_rate(), output)
+}
+
+/// Fission rate whose heat matches the heat demanded at `turbine_rate`.
+pub fn fission_for_turbine(turbine_rate: f32, output: &Output) -> f32 {
+    if output.get_fuel_potential() <= 0.0 {
+        return 0.0;
+    }
+    (turbine_rate * 75.0 / output.get_fuel_potential()).min(output.get_fuel_rod_ceiling())
+}
+
+/// Turbine rate demanding the heat produced at `fission_rate`, the inverse of
+/// `fission_for_turbine`.
+pub fn turbine_for_fission(fission_rate: f32, output: &Output) -> f32 {
+    (fission_rate * output.get_fuel_potential() / 75.0).clamp(0.0, 100.0)
+}
+
+/// Sets fission to the feedforward value, optionally trimmed by a PID on
+/// the temperature.
+#[derive(Clone, Default)]
+pub struct FeedforwardController {
+    trim: Option<PidController>,
+}
+
+impl FeedforwardController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The trim may push fission below the feedforward value as well.
+    pub fn with_trim(mut self, mut trim: PidController) -> Self {
+        trim.set_output_limits(-100.0, 100.0);
+        self.trim = Some(trim);
+        self
+    }
+}
+
+impl Controller for FeedforwardController {
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        let trim = match &mut self.trim {
+            Some(trim) => trim.compute(output.get_temperature(), dt),
+            None => 0.0,
+        };
+        input.set_fission_rate(feedforward_fission(output) + trim);
+    }
+
+    fn reset(&mut self) {
+        if let Some(trim) = &mut self.trim {
+            trim.reset();
+        }
+    }
+}
+
+/// Holds the power output at `target_power` instead of the temperature at a
+/// setpoint. Fission follows the turbine, trimmed by a PID on the power, and
+/// backs off to zero over the last `margin` degrees below the critical
+/// temperature whatever that costs in power.
+#[derive(Clone)]
+pub struct PowerController {
+    target_power: f32,
+    power_max: f32,
+    trim: PidController,
+    drive_turbine: bool,
+    critical_temperature: f32,
+    margin: f32,
+}
+
+impl PowerController {
+    pub fn new(target_power: f32, power_max: f32) -> Self {
+        let mut trim = PidController::new(0.002, 0.0002, 0.0).with_setpoint(target_power);
+        trim.set_output_limits(-100.0, 100.0);
+        Self {
+            target_power,
+            power_max,
+            trim,
+            drive_turbine: false,
+            critical_temperature: UNSAFE_TEMPERATURE,
+            margin: 500.0,
+        }
+    }
+
+    /// Also set the turbine rate delivering the target power at full
+    /// efficiency, instead of leaving it to another controller.
+    pub fn with_turbine(mut self) -> Self {
+        self.drive_turbine = true;
+        self
+    }
+
+    pub fn with_trim(mut self, kp: f32, ki: f32, kd: f32) -> Self {
+        self.trim.set_gains(kp, ki, kd);
+        self
+    }
+
+    /// Fission is scaled down from `critical_temperature - margin` and off
+    /// at `critical_temperature`.
+    pub fn with_critical_temperature(mut self, critical_temperature: f32, margin: f32) -> Self {
+        self.critical_temperature = critical_temperature;
+        self.margin = margin.max(f32::EPSILON);
+        self
+    }
+
+    pub fn get_target_power(&self) -> f32 {
+        self.target_power
+    }
+
+    pub fn set_target_power(&mut self, target_power: f32) {
+        let target_power = finite_or_zero(target_power);
+        self.target_power = target_power;
+        self.trim.set_setpoint(target_power);
+    }
+}
+
+impl Controller for PowerController {
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        if self.drive_turbine && self.power_max > 0.0 {
+            input.set_turbine_rate(self.target_power / self.power_max * 100.0);
+        }
+
+        let trim = self.trim.compute(output.get_power(), dt);
+        let headroom = ((self.critical_temperature - output.get_temperature()) / self.margin)
+            .clamp(0.0, 1.0);
+        input.set_fission_rate((feedforward_fission(output) + trim) * headroom);
+    }
+
+    fn reset(&mut self) {
+        self.trim.reset();
+    }
+}
+
+/// Shape of the load over time in percent of the max power, times and
+/// periods are in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "lowercase")]
+pub enum LoadProfile {
+    /// `max` for the first half of every period, `min` for the second.
+    Square {
+        min: f32,
+        max: f32,
+        period: f32,
+    },
+    Sine {
+        min: f32,
+        max: f32,
+        period: f32,
+    },
+    Triangle {
+        min: f32,
+        max: f32,
+        period: f32,
+    },
+    /// Leaves the load alone until `at`, then sets it to `to`.
+    Step {
+        at: f32,
+        to: f32,
+    },
+    /// Goes linearly from `from` to `to` over `duration`, then holds `to`.
+    Ramp {
+        from: f32,
+        to: f32,
+        duration: f32,
+    },
+}
+
+impl LoadProfile {
+    /// Load at `time`, `None` if the profile does not set one yet.
+    pub fn value_at(&self, time: f32) -> Option<f32> {
+        let phase = |period: f32| {
+            if period > 0.0 {
+                (time % period) / period
+            } else {
+                0.0
+            }
+        };
+        let value = match *self {
+            LoadProfile::Square { min, max, period } => {
+                if phase(period) < 0.5 {
+                    max
+                } else {
+                    min
+                }
+            }
+            LoadProfile::Sine { min, max, period } => {
+                let wave = (phase(period) * std::f32::consts::TAU).sin();
+                (min + max) / 2.0 + (max - min) / 2.0 * wave
+            }
+            LoadProfile::Triangle { min, max, period } => {
+                let phase = phase(period);
+                if phase < 0.5 {
+                    min + (max - min) * 2.0 * phase
+                } else {
+                    max - (max - min) * (2.0 * phase - 1.0)
+                }
+            }
+            LoadProfile::Step { at, to } => {
+                if time < at {
+                    return None;
+                }
+                to
+            }
+            LoadProfile::Ramp { from, to, duration } => {
+                if duration > 0.0 {
+                    from + (to - from) * (time / duration).min(1.0)
+                } else {
+                    to
+                }
+            }
+        };
+        Some(value)
+    }
+}
+
+impl Default for LoadProfile {
+    fn default() -> Self {
+        LoadProfile::Square {
+            min: 0.0,
+            max: 100.0,
+            period: 300.0,
+        }
+    }
+}
+
+/// Drives the load along a `LoadProfile`. The turbine rate is set to the
+/// same value for reactors without auto turbine.
+#[derive(Clone)]
+pub struct Load {
+    profile: LoadProfile,
+    tick: u64,
+}
+
+impl Load {
+    /// Square wave between `min` and `max`, `period` seconds long.
+    pub fn new(min: f32, max: f32, period: f32) -> Self {
+        Self::with_profile(LoadProfile::Square { min, max, period })
+    }
+
+    pub fn with_profile(profile: LoadProfile) -> Self {
+        Self { profile, tick: 0 }
+    }
+
+    pub fn get_profile(&self) -> &LoadProfile {
+        &self.profile
+    }
+}
+
+impl Default for Load {
+    fn default() -> Self {
+        Self::with_profile(LoadProfile::default())
+    }
+}
+
+impl Controller for Load {
+    fn update(&mut self, dt: f32, _output: &Output, input: &mut Input) {
+        self.tick += 1;
+
+        if let Some(load) = self.profile.value_at(self.tick as f32 * dt) {
+            input.set_load(load);
+            input.set_turbine_rate(load);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.tick = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fission rates `controller` commands for a series of temperatures.
+    fn commands(controller: &mut impl Controller, temperatures: &[f32]) -> Vec<f32> {
+        let mut reactor = Reactor::new(320.0, 4000.0);
+        temperatures
+            .iter()
+            .map(|&temperature| {
+                let reading = Reactor::builder().initial_temperature(temperature).build();
+                let input = reactor.get_input_mut();
+                controller.update(1.0 / 60.0, reading.get_output(), input);
+                input.get_fission_rate()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pid_integral_holds_while_saturated() {
+        let mut pid = PidController::new(0.1, 0.01, 0.0);
+        // Cold reactor against the default setpoint pins the output at 100
+        for _ in 0..600 {
+            assert_eq!(pid.compute(0.0, 1.0 / 60.0), 100.0);
+            assert_eq!(pid.get_integral(), 0.0);
+        }
+        // Integrates again once the output leaves the limit
+        assert!(pid.compute(pid.get_setpoint() - 100.0, 1.0 / 60.0) < 100.0);
+        assert!(pid.get_integral() > 0.0);
+    }
+
+    #[test]
+    fn pid_ignores_non_finite_settings() {
+        let mut pid = PidController::new(0.1, 0.01, 0.0);
+        pid.set_gains(f32::NAN, 0.01, f32::INFINITY);
+        pid.set_setpoint(f32::NAN);
+        assert_eq!(pid.get_gains(), (0.0, 0.01, 0.0));
+        assert!(pid.compute(100.0, 1.0 / 60.0).is_finite());
+    }
+
+    #[test]
+    fn feedforward_helpers_are_inverse() {
+        let mut reactor = Reactor::new(320.0, 4000.0);
+        reactor.update(1.0 / 60.0);
+        let output = reactor.get_output();
+        let fission = fission_for_turbine(50.0, output);
+        assert!(fission > 0.0 && fission < output.get_fuel_rod_ceiling());
+        assert!((turbine_for_fission(fission, output) - 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn safety_monitor_uses_effective_heating_rate() {
+        let reactor = Reactor::builder().thermal_mass(2.0).build();
+        let mut monitor = PredictiveSafetyMonitor::for_reactor(6000.0, &reactor);
+        let output = Output::for_test(5000.0, 0.0, 0.0);
+        monitor.update(1.0 / 60.0, &output, &mut Input::new());
+        // Half the default heating rate, the net heat of the output is zero
+        assert_eq!(monitor.projected_temperature(60), 5000.0 - 500.0);
+    }
+
+    #[test]
+    fn scheduled_gains_interpolate_and_extrapolate() {
+        let pid = ScheduledPid::new(vec![
+            (320.0, 4.0, 0.4, 0.0),
+            (80.0, 1.0, 0.1, 1.0),
+            (160.0, 2.0, 0.2, 0.0),
+        ]);
+        let close = |(kp, ki, kd): (f32, f32, f32), expected: (f32, f32, f32)| {
+            (kp - expected.0).abs() < 1e-4
+                && (ki - expected.1).abs() < 1e-4
+                && (kd - expected.2).abs() < 1e-4
+        };
+        assert!(close(pid.gains_at(120.0), (1.5, 0.15, 0.5)));
+        assert!(close(pid.gains_at(160.0), (2.0, 0.2, 0.0)));
+        assert!(close(pid.gains_at(40.0), (0.5, 0.05, 1.5)));
+        assert!(close(pid.gains_at(400.0), (5.0, 0.5, 0.0)));
+    }
+
+    #[test]
+    fn threshold_does_not_chatter_inside_band() {
+        let mut threshold = ThresholdController::new(5100.0, 4900.0, 100.0, 0.0);
+        let hovering = [4950.0, 5050.0, 4999.0, 5001.0, 5099.0, 4901.0];
+        assert!(commands(&mut threshold, &hovering).iter().all(|&f| f == 100.0));
+
+        commands(&mut threshold, &[5200.0]);
+        assert!(commands(&mut threshold, &hovering).iter().all(|&f| f == 0.0));
+        assert_eq!(commands(&mut threshold, &[4800.0]), [100.0]);
+    }
+
+    #[test]
+    fn scram_latches_until_reset() {
+        let mut scram = (
+            ThresholdController::new(10000.0, 0.0, 100.0, 0.0),
+            ScramController::new(6000.0, 4000.0),
+        );
+        let fission = commands(&mut scram, &[5000.0, 6100.0, 5500.0, 4500.0, 3900.0]);
+        assert_eq!(fission, [100.0, 0.0, 0.0, 0.0, 100.0]);
+        assert!(!scram.1.is_tripped());
+    }
+
+    #[test]
+    fn dither_does_not_accumulate() {
+        let output = Output::for_test(5000.0, 0.0, 0.0);
+        let mut input = Input::new();
+        input.set_fission_rate(50.0);
+        // The inner controller keeps the last command
+        let mut dither = DitherController::new((), 5.0, 7);
+        for _ in 0..1000 {
+            dither.update(1.0 / 60.0, &output, &mut input);
+            assert!((input.get_fission_rate() - 50.0).abs() <= 5.0);
+        }
+    }
+}
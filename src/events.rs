@@ -0,0 +1,145 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::{Controller, Input, Output};
+
+/// Something that happened during a run, at `time` seconds in.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum ReactorEvent {
+    /// The fission rate was commanded to its maximum.
+    FissionSaturated {
+        time: f32,
+    },
+    /// The fission rate was commanded below its maximum again.
+    FissionDesaturated {
+        time: f32,
+    },
+    TemperatureAbove {
+        time: f32,
+        threshold: f32,
+    },
+    TemperatureBelow {
+        time: f32,
+        threshold: f32,
+    },
+    Meltdown {
+        time: f32,
+    },
+}
+
+impl ReactorEvent {
+    pub fn get_time(&self) -> f32 {
+        match *self {
+            ReactorEvent::FissionSaturated { time }
+            | ReactorEvent::FissionDesaturated { time }
+            | ReactorEvent::TemperatureAbove { time, .. }
+            | ReactorEvent::TemperatureBelow { time, .. }
+            | ReactorEvent::Meltdown { time } => time,
+        }
+    }
+}
+
+impl Display for ReactorEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReactorEvent::FissionSaturated { time } => {
+                write!(f, "fission saturated at t={time:.1}s")
+            }
+            ReactorEvent::FissionDesaturated { time } => {
+                write!(f, "fission desaturated at t={time:.1}s")
+            }
+            ReactorEvent::TemperatureAbove { time, threshold } => {
+                write!(f, "temperature rose above {threshold} at t={time:.1}s")
+            }
+            ReactorEvent::TemperatureBelow { time, threshold } => {
+                write!(f, "temperature fell below {threshold} at t={time:.1}s")
+            }
+            ReactorEvent::Meltdown { time } => write!(f, "meltdown at t={time:.1}s"),
+        }
+    }
+}
+
+/// Records a `ReactorEvent` whenever a watched condition changes. Put it last
+/// in a tuple to see the input the other controllers settled on.
+pub struct EventLogger {
+    thresholds: Vec<f32>,
+    time: f32,
+    temperature: Option<f32>,
+    saturated: bool,
+    melted_down: bool,
+    events: Vec<ReactorEvent>,
+}
+
+impl EventLogger {
+    pub fn new() -> Self {
+        Self {
+            thresholds: Vec::new(),
+            time: 0.0,
+            temperature: None,
+            saturated: false,
+            melted_down: false,
+            events: Vec::new(),
+        }
+    }
+
+    /// Also log the temperature crossing `threshold`.
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.thresholds.push(threshold);
+        self
+    }
+
+    pub fn events(&self) -> &[ReactorEvent] {
+        &self.events
+    }
+}
+
+impl Default for EventLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Controller for EventLogger {
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        let time = self.time;
+        self.time += dt;
+
+        let temperature = output.get_temperature();
+        if let Some(previous) = self.temperature {
+            for &threshold in &self.thresholds {
+                if previous <= threshold && temperature > threshold {
+                    self.events
+                        .push(ReactorEvent::TemperatureAbove { time, threshold });
+                } else if previous > threshold && temperature <= threshold {
+                    self.events
+                        .push(ReactorEvent::TemperatureBelow { time, threshold });
+                }
+            }
+        }
+        self.temperature = Some(temperature);
+
+        let saturated = input.get_fission_rate() >= 100.0;
+        if saturated != self.saturated {
+            self.events.push(if saturated {
+                ReactorEvent::FissionSaturated { time }
+            } else {
+                ReactorEvent::FissionDesaturated { time }
+            });
+            self.saturated = saturated;
+        }
+
+        if output.is_melted_down() && !self.melted_down {
+            self.events.push(ReactorEvent::Meltdown { time });
+            self.melted_down = true;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.time = 0.0;
+        self.temperature = None;
+        self.saturated = false;
+        self.melted_down = false;
+        self.events.clear();
+    }
+}
@@ -0,0 +1,58 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FuelRod {
+    potential: f32,
+    /// Remaining durability in percent, the rod stops contributing at 0.
+    durability: f32,
+}
+
+impl FuelRod {
+    pub fn new(potential: f32, durability: f32) -> Self {
+        Self {
+            potential,
+            durability: durability.clamp(0.0, 100.0),
+        }
+    }
+
+    pub fn get_potential(&self) -> f32 {
+        self.potential
+    }
+
+    pub fn get_durability(&self) -> f32 {
+        self.durability
+    }
+
+    pub fn is_depleted(&self) -> bool {
+        self.durability <= 0.0
+    }
+
+    pub(crate) fn deplete(&mut self, amount: f32) {
+        self.durability = (self.durability - amount).max(0.0);
+    }
+}
+
+/// Fuel rods available in game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FuelRodType {
+    Normal,
+    Thorium,
+    Fulgurium,
+    Volatile,
+}
+
+impl FuelRodType {
+    pub fn potential(&self) -> f32 {
+        match self {
+            FuelRodType::Normal => 80.0,
+            FuelRodType::Thorium => 100.0,
+            FuelRodType::Fulgurium => 150.0,
+            FuelRodType::Volatile => 400.0,
+        }
+    }
+
+    /// A fresh rod of this type.
+    pub fn rod(&self) -> FuelRod {
+        FuelRod::new(self.potential(), 100.0)
+    }
+}
@@ -0,0 +1,166 @@
+use std::{fmt::Display, time::Duration};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{Controller, Load, LoadProfile, ReactorBuilder, Rng, RunReport, Simulation};
+
+/// Length of every fuzz run.
+const FUZZ_DURATION: Duration = Duration::from_secs(600);
+
+fn random_profile(rng: &mut Rng) -> LoadProfile {
+    let (a, b) = (rng.range(0.0, 100.0), rng.range(0.0, 100.0));
+    let (min, max) = (a.min(b), a.max(b));
+    let duration = FUZZ_DURATION.as_secs_f32();
+    match rng.next_u64() % 3 {
+        0 => LoadProfile::Square {
+            min,
+            max,
+            period: rng.range(10.0, duration / 2.0),
+        },
+        1 => LoadProfile::Step {
+            at: rng.range(0.0, duration / 2.0),
+            to: a,
+        },
+        _ => LoadProfile::Ramp {
+            from: a,
+            to: b,
+            duration: rng.range(1.0, duration),
+        },
+    }
+}
+
+/// A single run of `fuzz_controller`.
+#[derive(Debug, Clone)]
+pub struct FuzzRun {
+    profile: LoadProfile,
+    report: RunReport,
+}
+
+impl FuzzRun {
+    pub fn get_profile(&self) -> &LoadProfile {
+        &self.profile
+    }
+
+    pub fn get_report(&self) -> &RunReport {
+        &self.report
+    }
+
+    /// RMS of the temperature against the setpoint, from the ISE of the
+    /// report.
+    pub fn get_tracking_error(&self) -> f32 {
+        let ise = self.report.get_performance_indices().get_ise();
+        (ise / FUZZ_DURATION.as_secs_f32()).sqrt()
+    }
+}
+
+/// Worst cases over the runs of `fuzz_controller`.
+#[derive(Debug, Clone)]
+pub struct FuzzReport {
+    runs: Vec<FuzzRun>,
+}
+
+impl FuzzReport {
+    pub fn runs(&self) -> &[FuzzRun] {
+        &self.runs
+    }
+
+    pub fn unsafe_count(&self) -> usize {
+        self.runs.iter().filter(|run| !run.report.is_safe()).count()
+    }
+
+    /// The run reaching the highest temperature.
+    pub fn worst_temperature(&self) -> Option<&FuzzRun> {
+        self.runs.iter().max_by(|a, b| {
+            a.report
+                .get_max_temperature()
+                .total_cmp(&b.report.get_max_temperature())
+        })
+    }
+
+    /// The run straying furthest from the setpoint.
+    pub fn worst_tracking(&self) -> Option<&FuzzRun> {
+        self.runs
+            .iter()
+            .max_by(|a, b| a.get_tracking_error().total_cmp(&b.get_tracking_error()))
+    }
+}
+
+impl Display for FuzzReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} runs unsafe", self.unsafe_count(), self.runs.len())?;
+        if let Some(worst) = self.worst_temperature() {
+            write!(
+                f,
+                ", worst max_temp {} with {:?}",
+                worst.report.get_max_temperature(),
+                worst.profile
+            )?;
+        }
+        if let Some(worst) = self.worst_tracking() {
+            write!(
+                f,
+                ", worst tracking error {} with {:?}",
+                worst.get_tracking_error(),
+                worst.profile
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs a copy of `controller` against `n_runs` random square, step and ramp
+/// load profiles in parallel, tracking error is measured against `setpoint`.
+/// The profiles only depend on `seed`.
+pub fn fuzz_controller<C>(
+    reactor_builder: &ReactorBuilder,
+    controller: &C,
+    setpoint: f32,
+    n_runs: usize,
+    seed: u64,
+) -> FuzzReport
+where
+    C: Controller + Clone + Send + Sync,
+{
+    let mut rng = Rng::new(seed);
+    let profiles: Vec<LoadProfile> = (0..n_runs).map(|_| random_profile(&mut rng)).collect();
+
+    let runs = profiles
+        .into_par_iter()
+        .map(|profile| {
+            let (_, report) = Simulation::new(
+                FUZZ_DURATION,
+                reactor_builder.clone().build(),
+                (Load::with_profile(profile), controller.clone()),
+            )
+            .with_setpoint(setpoint)
+            .run_with_report();
+            FuzzRun { profile, report }
+        })
+        .collect();
+
+    FuzzReport { runs }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PidController, Reactor, SETPOINT_DEFAULT};
+
+    #[test]
+    fn same_seed_reproduces_fuzz_runs() {
+        let builder = Reactor::builder().fuel_potential(320.0);
+        let pid = PidController::new(0.05, 0.001, 0.0);
+        let errors = |seed| {
+            fuzz_controller(&builder, &pid, SETPOINT_DEFAULT, 2, seed)
+                .runs()
+                .iter()
+                .map(FuzzRun::get_tracking_error)
+                .collect::<Vec<_>>()
+        };
+        let first = errors(1);
+        assert_eq!(first.len(), 2);
+        assert!(first.iter().all(|&error| error > 0.0 && error.is_finite()));
+        assert_eq!(first, errors(1));
+        assert_ne!(first, errors(2));
+    }
+}
@@ -0,0 +1,67 @@
+/// Numerical integration of a single state variable over one tick.
+pub trait Integrator {
+    /// Advances `value` by `dt` given its `derivative`.
+    fn step(&self, value: f32, dt: f32, derivative: &dyn Fn(f32) -> f32) -> f32;
+}
+
+/// Forward Euler.
+pub struct Euler;
+
+impl Integrator for Euler {
+    fn step(&self, value: f32, dt: f32, derivative: &dyn Fn(f32) -> f32) -> f32 {
+        value + derivative(value) * dt
+    }
+}
+
+/// Classic fourth order Runge-Kutta.
+pub struct Rk4;
+
+impl Integrator for Rk4 {
+    fn step(&self, value: f32, dt: f32, derivative: &dyn Fn(f32) -> f32) -> f32 {
+        let k1 = derivative(value);
+        let k2 = derivative(value + k1 * dt / 2.0);
+        let k3 = derivative(value + k2 * dt / 2.0);
+        let k4 = derivative(value + k3 * dt);
+        value + (k1 + 2.0 * k2 + 2.0 * k3 + k4) * dt / 6.0
+    }
+}
+
+/// Integrator used by a `Reactor`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IntegratorKind {
+    #[default]
+    Euler,
+    Rk4,
+}
+
+impl Integrator for IntegratorKind {
+    fn step(&self, value: f32, dt: f32, derivative: &dyn Fn(f32) -> f32) -> f32 {
+        match self {
+            IntegratorKind::Euler => Euler.step(value, dt, derivative),
+            IntegratorKind::Rk4 => Rk4.step(value, dt, derivative),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Heat left after decaying at `dy/dt = -y / 2` for 10 seconds at 10 steps
+    /// per second, relative to the exact exponential.
+    fn decay_error(integrator: impl Integrator) -> f32 {
+        let mut value = 5000.0;
+        for _ in 0..100 {
+            value = integrator.step(value, 0.1, &|value| -value / 2.0);
+        }
+        let exact = 5000.0 * (-5.0f32).exp();
+        (value - exact).abs() / exact
+    }
+
+    #[test]
+    fn rk4_conserves_heat_better_than_euler() {
+        let (euler, rk4) = (decay_error(Euler), decay_error(Rk4));
+        assert!(rk4 < 1e-3, "rk4 error {rk4}");
+        assert!(rk4 < euler / 100.0, "rk4 error {rk4}, euler error {euler}");
+    }
+}
@@ -1,6 +1,43 @@
 pub use reactor::*;
+pub use components::*;
 pub use controller::*;
+pub use status::*;
+pub use analysis::*;
+pub use simulator::*;
+pub use recorder::*;
+#[cfg(feature = "parallel")]
+pub use batch::*;
+#[cfg(feature = "parallel")]
+pub use fuzz::*;
+pub use fuel::*;
+pub use signals::*;
+pub use report::*;
+pub use integrator::*;
+pub use noise::*;
+pub use scenario::*;
+pub use events::*;
+pub use measurements::*;
+#[cfg(feature = "plot")]
+pub use plot::*;
 
 mod reactor;
+mod components;
 mod controller;
-mod simulator;
\ No newline at end of file
+mod simulator;
+mod status;
+mod analysis;
+mod recorder;
+#[cfg(feature = "parallel")]
+mod batch;
+#[cfg(feature = "parallel")]
+mod fuzz;
+mod fuel;
+mod signals;
+mod report;
+mod integrator;
+mod noise;
+mod scenario;
+mod events;
+mod measurements;
+#[cfg(feature = "plot")]
+mod plot;
\ No newline at end of file
@@ -0,0 +1,353 @@
+use std::{path::Path, time::Duration};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    fission_for_turbine, turbine_for_fission, Controller, Input, Output, PerformanceIndices,
+    DEFAULT_TICK_RATE,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct TrackingScore {
+    fission_target: f32,
+    fission_optimal: f32,
+    turbine_target: f32,
+    turbine_optimal: f32,
+}
+
+impl TrackingScore {
+    pub fn get_fission_target(&self) -> f32 {
+        self.fission_target
+    }
+
+    pub fn get_fission_optimal(&self) -> f32 {
+        self.fission_optimal
+    }
+
+    pub fn get_turbine_target(&self) -> f32 {
+        self.turbine_target
+    }
+
+    pub fn get_turbine_optimal(&self) -> f32 {
+        self.turbine_optimal
+    }
+}
+
+fn rms_error(actual: &[f32], reference: &[f32]) -> f32 {
+    if actual.is_empty() {
+        return 0.0;
+    }
+    let sum: f32 = actual
+        .iter()
+        .zip(reference)
+        .map(|(actual, reference)| (actual - reference).powi(2))
+        .sum();
+    (sum / actual.len() as f32).sqrt()
+}
+
+/// Signals of a single tick, other than the temperature.
+struct Sample {
+    fission: f32,
+    fission_target: f32,
+    fission_optimal: f32,
+    turbine: f32,
+    turbine_target: f32,
+    turbine_optimal: f32,
+    power: f32,
+}
+
+/// Time series of a run, recorded as a controller.
+pub struct Mesurements {
+    temperature: Vec<f32>,
+    fission: Vec<f32>,
+    fission_target: Vec<f32>,
+    fission_optimal: Vec<f32>,
+    turbine: Vec<f32>,
+    turbine_target: Vec<f32>,
+    turbine_optimal: Vec<f32>,
+    power: Vec<f32>,
+    power_max: f32,
+    /// Ticks simulated per second.
+    tick_rate: f32,
+    /// Ticks per stored sample.
+    decimation: u64,
+    /// Start of the run that is not recorded.
+    warmup: Duration,
+    /// `warmup` in ticks, known once the first tick gives the time delta.
+    warmup_ticks: u64,
+    ticks: u64,
+    window_ticks: u64,
+    window_max_temperature: f32,
+    /// Last tick of the window not stored yet.
+    window_last: Option<Sample>,
+}
+
+impl Mesurements {
+    pub fn with_capacity(capacity: usize, power_max: f32) -> Self {
+        Self {
+            temperature: Vec::with_capacity(capacity),
+            fission: Vec::with_capacity(capacity),
+            fission_target: Vec::with_capacity(capacity),
+            fission_optimal: Vec::with_capacity(capacity),
+            turbine: Vec::with_capacity(capacity),
+            turbine_target: Vec::with_capacity(capacity),
+            turbine_optimal: Vec::with_capacity(capacity),
+            power: Vec::with_capacity(capacity),
+            power_max,
+            tick_rate: DEFAULT_TICK_RATE,
+            decimation: 1,
+            warmup: Duration::ZERO,
+            warmup_ticks: 0,
+            ticks: 0,
+            window_ticks: 0,
+            window_max_temperature: f32::NEG_INFINITY,
+            window_last: None,
+        }
+    }
+
+    /// Ticks per second of the simulation recorded, `DEFAULT_TICK_RATE`
+    /// unless set.
+    pub fn with_tick_rate(mut self, tick_rate: f32) -> Self {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    pub fn get_tick_rate(&self) -> f32 {
+        self.tick_rate
+    }
+
+    /// Only store one sample every `decimation` ticks, to bound the memory of
+    /// long runs. The temperature sample is the peak of its ticks so no
+    /// excursion is lost, the other signals are sampled on the last tick.
+    pub fn with_decimation(mut self, decimation: u64) -> Self {
+        self.decimation = decimation.max(1);
+        self
+    }
+
+    pub fn get_decimation(&self) -> u64 {
+        self.decimation
+    }
+
+    /// Skips the first `warmup` of the run, like `Simulation::with_warmup`.
+    /// Converted to ticks with the time delta of the first update.
+    pub fn with_warmup(mut self, warmup: Duration) -> Self {
+        self.warmup = warmup;
+        self.warmup_ticks = (warmup.as_secs_f64() * self.tick_rate as f64).round() as u64;
+        self
+    }
+
+    pub fn get_warmup_ticks(&self) -> u64 {
+        self.warmup_ticks
+    }
+
+    /// Stored samples per second.
+    pub fn get_sample_rate(&self) -> f32 {
+        self.tick_rate / self.decimation as f32
+    }
+
+    pub fn get_temperature(&self) -> &[f32] {
+        &self.temperature
+    }
+
+    pub fn get_fission(&self) -> &[f32] {
+        &self.fission
+    }
+
+    pub fn get_fission_target(&self) -> &[f32] {
+        &self.fission_target
+    }
+
+    pub fn get_fission_optimal(&self) -> &[f32] {
+        &self.fission_optimal
+    }
+
+    pub fn get_turbine(&self) -> &[f32] {
+        &self.turbine
+    }
+
+    pub fn get_turbine_target(&self) -> &[f32] {
+        &self.turbine_target
+    }
+
+    pub fn get_turbine_optimal(&self) -> &[f32] {
+        &self.turbine_optimal
+    }
+
+    pub fn get_power(&self) -> &[f32] {
+        &self.power
+    }
+
+    pub fn get_power_max(&self) -> f32 {
+        self.power_max
+    }
+
+    /// RMS error of the actual fission and turbine rates against their
+    /// targets and optimal values.
+    pub fn tracking_error(&self) -> TrackingScore {
+        TrackingScore {
+            fission_target: rms_error(&self.fission, &self.fission_target),
+            fission_optimal: rms_error(&self.fission, &self.fission_optimal),
+            turbine_target: rms_error(&self.turbine, &self.turbine_target),
+            turbine_optimal: rms_error(&self.turbine, &self.turbine_optimal),
+        }
+    }
+
+    /// Indices of the stored temperatures. With decimation those are the
+    /// peak of every window, which overstates the error above the setpoint
+    /// and understates it below, the `RunReport` has the exact indices.
+    pub fn performance_indices(&self, setpoint: f32) -> PerformanceIndices {
+        PerformanceIndices::from_temperatures(
+            &self.temperature,
+            1.0 / self.get_sample_rate(),
+            setpoint,
+        )
+    }
+
+    pub fn write_csv(&self, path: impl AsRef<Path>) -> Result<()> {
+        #[derive(Serialize)]
+        struct Row {
+            tick: usize,
+            temperature: f32,
+            fission: f32,
+            fission_target: f32,
+            fission_optimal: f32,
+            turbine: f32,
+            turbine_target: f32,
+        }
+
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut writer = csv::Writer::from_path(path)?;
+        let decimation = self.decimation as usize;
+        let warmup = self.warmup_ticks as usize;
+        let last_tick = (self.ticks as usize).saturating_sub(1);
+        for sample in 0..self.temperature.len() {
+            writer.serialize(Row {
+                // The last window may be cut short by the end of the run
+                tick: (warmup + (sample + 1) * decimation - 1).min(last_tick),
+                temperature: self.temperature[sample],
+                fission: self.fission[sample],
+                fission_target: self.fission_target[sample],
+                fission_optimal: self.fission_optimal[sample],
+                turbine: self.turbine[sample],
+                turbine_target: self.turbine_target[sample],
+            })?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+impl Mesurements {
+    /// Stores the current window.
+    fn flush(&mut self) {
+        let Some(sample) = self.window_last.take() else {
+            return;
+        };
+        self.window_ticks = 0;
+
+        self.temperature.push(std::mem::replace(
+            &mut self.window_max_temperature,
+            f32::NEG_INFINITY,
+        ));
+        self.fission.push(sample.fission);
+        self.fission_target.push(sample.fission_target);
+        self.fission_optimal.push(sample.fission_optimal);
+        self.turbine.push(sample.turbine);
+        self.turbine_target.push(sample.turbine_target);
+        self.turbine_optimal.push(sample.turbine_optimal);
+        self.power.push(sample.power);
+    }
+}
+
+impl Controller for Mesurements {
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        if self.ticks == 0 && dt > 0.0 {
+            self.warmup_ticks = (self.warmup.as_secs_f64() / dt as f64).round() as u64;
+        }
+        self.ticks += 1;
+        if self.ticks <= self.warmup_ticks {
+            return;
+        }
+
+        self.window_max_temperature = self.window_max_temperature.max(output.get_temperature());
+        self.window_ticks += 1;
+        self.window_last = Some(Sample {
+            fission: output.get_fission_rate(),
+            fission_target: input.get_fission_rate(),
+            fission_optimal: fission_for_turbine(input.get_turbine_rate(), output),
+            turbine: output.get_turbine_rate(),
+            turbine_target: input.get_turbine_rate(),
+            turbine_optimal: turbine_for_fission(input.get_fission_rate(), output),
+            power: output.get_power(),
+        });
+        if self.window_ticks >= self.decimation {
+            self.flush();
+        }
+    }
+
+    /// Stores the last window even if the run ended before it completed.
+    fn finish(&mut self) {
+        self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(mesurements: &mut Mesurements, temperatures: impl IntoIterator<Item = f32>) {
+        let mut input = Input::new();
+        for temperature in temperatures {
+            let output = Output::for_test(temperature, 0.0, 0.0);
+            mesurements.update(1.0 / DEFAULT_TICK_RATE, &output, &mut input);
+        }
+    }
+
+    #[test]
+    fn decimation_keeps_temperature_peaks() {
+        let mut mesurements = Mesurements::with_capacity(0, 4000.0).with_decimation(3);
+        record(&mut mesurements, [1.0, 7.0, 2.0, 3.0, 4.0, 9.0, 5.0]);
+        assert_eq!(mesurements.get_temperature(), [7.0, 9.0]);
+        assert_eq!(mesurements.get_fission().len(), 2);
+        assert_eq!(mesurements.get_sample_rate(), DEFAULT_TICK_RATE / 3.0);
+    }
+
+    #[test]
+    fn warmup_is_not_recorded() {
+        let mut mesurements =
+            Mesurements::with_capacity(0, 4000.0).with_warmup(Duration::from_secs(1));
+        record(&mut mesurements, (0..90).map(|tick| tick as f32));
+        assert_eq!(mesurements.get_temperature().len(), 30);
+        assert_eq!(mesurements.get_temperature()[0], 60.0);
+    }
+
+    #[test]
+    fn warmup_follows_the_time_delta() {
+        let mut mesurements = Mesurements::with_capacity(0, 4000.0)
+            .with_warmup(Duration::from_secs(1))
+            .with_tick_rate(30.0);
+        let mut input = Input::new();
+        for tick in 0..45 {
+            let output = Output::for_test(tick as f32, 0.0, 0.0);
+            mesurements.update(1.0 / 30.0, &output, &mut input);
+        }
+        assert_eq!(mesurements.get_warmup_ticks(), 30);
+        assert_eq!(mesurements.get_temperature().len(), 15);
+        assert_eq!(mesurements.get_temperature()[0], 30.0);
+    }
+
+    #[test]
+    fn partial_window_is_stored_on_finish() {
+        let mut mesurements = Mesurements::with_capacity(0, 4000.0).with_decimation(4);
+        record(&mut mesurements, [1.0, 5.0, 2.0, 3.0, 9.0, 4.0]);
+        assert_eq!(mesurements.get_temperature(), [5.0]);
+        mesurements.finish();
+        assert_eq!(mesurements.get_temperature(), [5.0, 9.0]);
+        assert_eq!(mesurements.get_fission().len(), 2);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::Output;
+
+/// Small deterministic generator (SplitMix64), so runs reproduce from a seed.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in `[min, max)`.
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Standard normal, using Box-Muller.
+    pub fn normal(&mut self) -> f32 {
+        let u1 = self.next_f32().max(f32::MIN_POSITIVE);
+        let u2 = self.next_f32();
+        (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+    }
+}
+
+/// Gaussian noise on the readings passed to the controller.
+#[derive(Debug, Clone, Copy)]
+pub struct NoiseConfig {
+    temperature_stddev: f32,
+    load_stddev: f32,
+    seed: u64,
+}
+
+impl NoiseConfig {
+    pub fn new(temperature_stddev: f32, seed: u64) -> Self {
+        Self {
+            temperature_stddev,
+            load_stddev: 0.0,
+            seed,
+        }
+    }
+
+    pub fn with_load_stddev(mut self, load_stddev: f32) -> Self {
+        self.load_stddev = load_stddev;
+        self
+    }
+
+    pub fn get_temperature_stddev(&self) -> f32 {
+        self.temperature_stddev
+    }
+
+    pub fn get_load_stddev(&self) -> f32 {
+        self.load_stddev
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+pub(crate) struct SensorNoise {
+    config: NoiseConfig,
+    rng: Rng,
+}
+
+impl SensorNoise {
+    pub(crate) fn new(config: NoiseConfig) -> Self {
+        Self {
+            config,
+            rng: Rng::new(config.seed),
+        }
+    }
+
+    /// Copy of `output` with noisy readings, the reactor state is untouched.
+    pub(crate) fn apply(&mut self, output: &Output) -> Output {
+        let temperature = self.rng.normal() * self.config.temperature_stddev;
+        let load = self.rng.normal() * self.config.load_stddev;
+        output.perturbed(temperature, load)
+    }
+}
@@ -0,0 +1,538 @@
+use std::{ops::Range, path::Path};
+
+use anyhow::Result;
+use plotters::{
+    backend::{BitMapBackend, DrawingBackend, SVGBackend},
+    chart::ChartBuilder,
+    coord::Shift,
+    drawing::{DrawingArea, IntoDrawingArea},
+    element::PathElement,
+    series::LineSeries,
+    style::{full_palette::ORANGE, Color, IntoFont, Palette, Palette99, BLACK, GREEN, RED, WHITE},
+};
+
+use crate::{Mesurements, MAX_TEMPERATURE, SETPOINT_DEFAULT};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Png,
+    Svg,
+}
+
+impl GraphFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GraphFormat::Png => "png",
+            GraphFormat::Svg => "svg",
+        }
+    }
+}
+
+/// Fraction of the data range added above and below when autoscaling.
+const AUTOSCALE_MARGIN: f32 = 0.05;
+
+/// Size of a single graph, the dashboard stacks three of them.
+#[derive(Debug, Clone, Copy)]
+pub struct GraphConfig {
+    width: u32,
+    height: u32,
+    caption_font_size: u32,
+    format: GraphFormat,
+    autoscale: bool,
+}
+
+impl GraphConfig {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_caption_font_size(mut self, caption_font_size: u32) -> Self {
+        self.caption_font_size = caption_font_size;
+        self
+    }
+
+    pub fn with_format(mut self, format: GraphFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn get_format(&self) -> GraphFormat {
+        self.format
+    }
+
+    /// Fit the y axis to the data instead of the fixed range of each graph.
+    /// The fixed range keeps graphs of different runs comparable, autoscaling
+    /// shows small deviations.
+    pub fn with_autoscale(mut self, autoscale: bool) -> Self {
+        self.autoscale = autoscale;
+        self
+    }
+
+    pub fn is_autoscale(&self) -> bool {
+        self.autoscale
+    }
+
+    /// `fixed`, or the range of the finite values in `series` with a margin
+    /// when autoscaling.
+    fn y_range(&self, fixed: Range<f32>, series: &[&[f32]]) -> Range<f32> {
+        if !self.autoscale {
+            return fixed;
+        }
+        let (min, max) = series
+            .iter()
+            .flat_map(|values| values.iter().copied())
+            .filter(|value| value.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+        if min > max {
+            return fixed;
+        }
+        let margin = ((max - min) * AUTOSCALE_MARGIN).max(1.0);
+        (min - margin)..(max + margin)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn dashboard_size(&self) -> (u32, u32) {
+        (self.width, self.height * 3)
+    }
+}
+
+impl Default for GraphConfig {
+    fn default() -> Self {
+        Self {
+            width: 2048,
+            height: 768,
+            caption_font_size: 50,
+            format: GraphFormat::Png,
+            autoscale: false,
+        }
+    }
+}
+
+/// Graphs `Mesurements::write_with` can draw.
+#[derive(Debug, Clone, Copy)]
+enum Graph {
+    Temperature,
+    Fission,
+    Turbine,
+    Power,
+    Dashboard,
+}
+
+impl Mesurements {
+    /// Seconds since the start of the run of every recorded tick.
+    fn time_axis(&self) -> impl Iterator<Item = f32> + '_ {
+        let start = self.start();
+        (0..self.get_temperature().len())
+            .map(move |tick| start + tick as f32 / self.get_sample_rate())
+    }
+
+    /// Seconds of warmup before the first recorded tick.
+    fn start(&self) -> f32 {
+        self.get_warmup_ticks() as f32 / self.get_tick_rate()
+    }
+
+    fn time_range(&self) -> Range<f32> {
+        let start = self.start();
+        start..start + self.get_temperature().len() as f32 / self.get_sample_rate()
+    }
+
+    fn draw_temperature_graph<DB>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        config: &GraphConfig,
+    ) -> Result<()>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+    {
+        root.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(root)
+            .caption(
+                "Temperature",
+                ("sans-serif", config.caption_font_size).into_font(),
+            )
+            .margin(5)
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .build_cartesian_2d(
+                self.time_range(),
+                config.y_range(
+                    0.0..MAX_TEMPERATURE,
+                    &[self.get_temperature(), &[SETPOINT_DEFAULT]],
+                ),
+            )?;
+
+        chart
+            .configure_mesh()
+            .x_desc("time (s)")
+            .x_labels(10)
+            .y_labels(10)
+            .draw()?;
+
+        chart
+            .draw_series(LineSeries::new(
+                self.time_axis().zip(std::iter::repeat(SETPOINT_DEFAULT)),
+                &ORANGE,
+            ))?
+            .label(format!("setpoint ({SETPOINT_DEFAULT})"))
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], ORANGE));
+
+        chart
+            .draw_series(LineSeries::new(
+                self.time_axis().zip(self.get_temperature().iter().copied()),
+                &RED,
+            ))?
+            .label("temperature")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+
+        Ok(())
+    }
+
+    fn draw_fission_graph<DB>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        config: &GraphConfig,
+    ) -> Result<()>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+    {
+        root.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(root)
+            .caption(
+                "Fission",
+                ("sans-serif", config.caption_font_size).into_font(),
+            )
+            .margin(5)
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .build_cartesian_2d(
+                self.time_range(),
+                config.y_range(
+                    0.0..100.0,
+                    &[
+                        self.get_fission(),
+                        self.get_fission_target(),
+                        self.get_fission_optimal(),
+                    ],
+                ),
+            )?;
+
+        chart
+            .configure_mesh()
+            .x_desc("time (s)")
+            .x_labels(10)
+            .y_labels(10)
+            .draw()?;
+
+        chart
+            .draw_series(LineSeries::new(
+                self.time_axis()
+                    .zip(self.get_fission_optimal().iter().copied()),
+                &GREEN,
+            ))?
+            .label("optimal")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], GREEN));
+
+        chart
+            .draw_series(LineSeries::new(
+                self.time_axis()
+                    .zip(self.get_fission_target().iter().copied()),
+                &ORANGE,
+            ))?
+            .label("target")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], ORANGE));
+
+        chart
+            .draw_series(LineSeries::new(
+                self.time_axis().zip(self.get_fission().iter().copied()),
+                &RED,
+            ))?
+            .label("fission")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+
+        Ok(())
+    }
+
+    fn draw_turbine_graph<DB>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        config: &GraphConfig,
+    ) -> Result<()>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+    {
+        root.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(root)
+            .caption(
+                "Turbine",
+                ("sans-serif", config.caption_font_size).into_font(),
+            )
+            .margin(5)
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .build_cartesian_2d(
+                self.time_range(),
+                config.y_range(0.0..100.0, &[self.get_turbine(), self.get_turbine_target()]),
+            )?;
+
+        chart
+            .configure_mesh()
+            .x_desc("time (s)")
+            .x_labels(10)
+            .y_labels(10)
+            .draw()?;
+
+        chart
+            .draw_series(LineSeries::new(
+                self.time_axis().zip(self.get_turbine().iter().copied()),
+                &RED,
+            ))?
+            .label("turbine")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
+
+        chart
+            .draw_series(LineSeries::new(
+                self.time_axis()
+                    .zip(self.get_turbine_target().iter().copied()),
+                &ORANGE,
+            ))?
+            .label("target")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], ORANGE));
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+
+        Ok(())
+    }
+
+    fn draw_power_graph<DB>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        config: &GraphConfig,
+    ) -> Result<()>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+    {
+        root.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(root)
+            .caption(
+                "Power",
+                ("sans-serif", config.caption_font_size).into_font(),
+            )
+            .margin(5)
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .build_cartesian_2d(
+                self.time_range(),
+                config.y_range(0.0..self.get_power_max(), &[self.get_power()]),
+            )?;
+
+        chart
+            .configure_mesh()
+            .x_desc("time (s)")
+            .x_labels(10)
+            .y_labels(10)
+            .draw()?;
+
+        chart
+            .draw_series(LineSeries::new(
+                self.time_axis().zip(self.get_power().iter().copied()),
+                &RED,
+            ))?
+            .label("power")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+
+        Ok(())
+    }
+
+    /// Temperature, fission and turbine stacked on a shared time axis.
+    fn draw_dashboard<DB>(&self, root: &DrawingArea<DB, Shift>, config: &GraphConfig) -> Result<()>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+    {
+        let areas = root.split_evenly((3, 1));
+        self.draw_temperature_graph(&areas[0], config)?;
+        self.draw_fission_graph(&areas[1], config)?;
+        self.draw_turbine_graph(&areas[2], config)?;
+
+        Ok(())
+    }
+
+    fn draw<DB>(
+        &self,
+        graph: Graph,
+        root: &DrawingArea<DB, Shift>,
+        config: &GraphConfig,
+    ) -> Result<()>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+    {
+        match graph {
+            Graph::Temperature => self.draw_temperature_graph(root, config),
+            Graph::Fission => self.draw_fission_graph(root, config),
+            Graph::Turbine => self.draw_turbine_graph(root, config),
+            Graph::Power => self.draw_power_graph(root, config),
+            Graph::Dashboard => self.draw_dashboard(root, config),
+        }
+    }
+
+    /// Draws `graph` to `path` with the backend of the configured format.
+    fn write_with(
+        &self,
+        path: &Path,
+        size: (u32, u32),
+        config: &GraphConfig,
+        graph: Graph,
+    ) -> Result<()> {
+        match config.format {
+            GraphFormat::Png => {
+                let root = BitMapBackend::new(path, size).into_drawing_area();
+                self.draw(graph, &root, config)?;
+                root.present()?;
+            }
+            GraphFormat::Svg => {
+                let root = SVGBackend::new(path, size).into_drawing_area();
+                self.draw(graph, &root, config)?;
+                root.present()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_temperature_graph(
+        &self,
+        path: impl AsRef<Path>,
+        config: &GraphConfig,
+    ) -> Result<()> {
+        self.write_with(path.as_ref(), config.size(), config, Graph::Temperature)
+    }
+
+    pub fn write_fission_graph(&self, path: impl AsRef<Path>, config: &GraphConfig) -> Result<()> {
+        self.write_with(path.as_ref(), config.size(), config, Graph::Fission)
+    }
+
+    pub fn write_turbine_graph(&self, path: impl AsRef<Path>, config: &GraphConfig) -> Result<()> {
+        self.write_with(path.as_ref(), config.size(), config, Graph::Turbine)
+    }
+
+    pub fn write_power_graph(&self, path: impl AsRef<Path>, config: &GraphConfig) -> Result<()> {
+        self.write_with(path.as_ref(), config.size(), config, Graph::Power)
+    }
+
+    pub fn write_dashboard(&self, path: impl AsRef<Path>, config: &GraphConfig) -> Result<()> {
+        self.write_with(
+            path.as_ref(),
+            config.dashboard_size(),
+            config,
+            Graph::Dashboard,
+        )
+    }
+
+    pub fn write_all_graphs(&self, path: impl AsRef<Path>, config: &GraphConfig) -> Result<()> {
+        let path = path.as_ref();
+        let extension = config.format.extension();
+        self.write_temperature_graph(path.join(format!("temperature.{extension}")), config)?;
+        self.write_fission_graph(path.join(format!("fission.{extension}")), config)?;
+        self.write_turbine_graph(path.join(format!("turbine.{extension}")), config)?;
+        self.write_power_graph(path.join(format!("power.{extension}")), config)?;
+        self.write_dashboard(path.join(format!("dashboard.{extension}")), config)?;
+
+        Ok(())
+    }
+}
+
+/// Draws labeled series sampled at `tick_rate` per second on one chart,
+/// colored by their index.
+pub fn plot_overlay(
+    series: &[(&str, &[f32])],
+    path: impl AsRef<Path>,
+    y_range: Range<f32>,
+    tick_rate: f32,
+) -> Result<()> {
+    let config = GraphConfig::default();
+    let root = BitMapBackend::new(path.as_ref(), config.size()).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let duration = series
+        .iter()
+        .map(|(_, values)| values.len())
+        .max()
+        .unwrap_or_default() as f32
+        / tick_rate;
+
+    let mut chart = ChartBuilder::on(&root)
+        .margin(5)
+        .x_label_area_size(40)
+        .y_label_area_size(40)
+        .build_cartesian_2d(0.0..duration, y_range)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("time (s)")
+        .x_labels(10)
+        .y_labels(10)
+        .draw()?;
+
+    for (index, (label, values)) in series.iter().enumerate() {
+        let color = Palette99::pick(index).to_rgba();
+        chart
+            .draw_series(LineSeries::new(
+                (0..values.len())
+                    .map(|tick| tick as f32 / tick_rate)
+                    .zip(values.iter().copied()),
+                color.stroke_width(2),
+            ))?
+            .label(*label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+
+    Ok(())
+}
@@ -1,25 +1,131 @@
-use std::fmt::Debug;
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Display},
+};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize)]
+use crate::{
+    Core, FuelRod, FuelRodType, Integrator, IntegratorKind, SafetyLimits, Turbine,
+    DEFAULT_SLEW_RATE, DEFAULT_TIME_CONSTANT, MAX_TEMPERATURE,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "UncheckedInput")]
 pub struct Input {
     fission_rate: f32,
     turbine_rate: f32,
     load: f32,
+    powered: bool,
+    #[serde(skip)]
+    saturation_count: u64,
+    /// Rods waiting to be inserted on the next update.
+    #[serde(skip)]
+    fuel_rod_inserts: Vec<FuelRod>,
+}
+
+/// A rate outside of 0..100 passed to one of the `Input::try_set_*` methods.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutOfRange {
+    value: f32,
+}
+
+impl OutOfRange {
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+}
+
+impl Display for OutOfRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is outside of 0..100", self.value)
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+/// `Input` as read from a file, before clamping.
+#[derive(Deserialize)]
+struct UncheckedInput {
+    #[serde(default)]
+    fission_rate: f32,
+    #[serde(default)]
+    turbine_rate: f32,
+    #[serde(default)]
+    load: f32,
+    #[serde(default = "powered_default")]
+    powered: bool,
+}
+
+fn powered_default() -> bool {
+    true
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<UncheckedInput> for Input {
+    fn from(unchecked: UncheckedInput) -> Self {
+        let mut input = Input::new();
+        input.set_fission_rate(unchecked.fission_rate);
+        input.set_turbine_rate(unchecked.turbine_rate);
+        input.set_load(unchecked.load);
+        input.set_powered(unchecked.powered);
+        input
+    }
 }
 
 impl Input {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             fission_rate: 0.0,
             turbine_rate: 0.0,
             load: 0.0,
+            powered: true,
+            saturation_count: 0,
+            fuel_rod_inserts: Vec::new(),
         }
     }
 
+    /// Clamps `value` to 0..100, and NaN to 0, counting it if it had to be
+    /// clamped.
+    fn clamp(&mut self, value: f32) -> f32 {
+        // A NaN from a division by zero in a controller would spread through
+        // the whole reactor state, stop it here
+        if value.is_nan() {
+            self.saturation_count += 1;
+            return 0.0;
+        }
+        let clamped = value.clamp(0.0, 100.0);
+        if clamped != value {
+            self.saturation_count += 1;
+        }
+        clamped
+    }
+
+    fn check(value: f32) -> Result<f32, OutOfRange> {
+        if (0.0..=100.0).contains(&value) {
+            Ok(value)
+        } else {
+            Err(OutOfRange { value })
+        }
+    }
+
+    /// Number of times a setter had to clamp its value.
+    pub fn get_saturation_count(&self) -> u64 {
+        self.saturation_count
+    }
+
     pub fn set_fission_rate(&mut self, fission_rate: f32) {
-        self.fission_rate = fission_rate.clamp(0.0, 100.0);
+        self.fission_rate = self.clamp(fission_rate);
+    }
+
+    pub fn try_set_fission_rate(&mut self, fission_rate: f32) -> Result<(), OutOfRange> {
+        self.fission_rate = Self::check(fission_rate)?;
+        Ok(())
     }
 
     pub fn get_fission_rate(&self) -> f32 {
@@ -27,7 +133,12 @@ impl Input {
     }
 
     pub fn set_turbine_rate(&mut self, turbine_rate: f32) {
-        self.turbine_rate = turbine_rate.clamp(0.0, 100.0);
+        self.turbine_rate = self.clamp(turbine_rate);
+    }
+
+    pub fn try_set_turbine_rate(&mut self, turbine_rate: f32) -> Result<(), OutOfRange> {
+        self.turbine_rate = Self::check(turbine_rate)?;
+        Ok(())
     }
 
     pub fn get_turbine_rate(&self) -> f32 {
@@ -35,33 +146,76 @@ impl Input {
     }
 
     pub fn set_load(&mut self, load: f32) {
-        self.load = load.clamp(0.0, 100.0);
+        self.load = self.clamp(load);
+    }
+
+    pub fn try_set_load(&mut self, load: f32) -> Result<(), OutOfRange> {
+        self.load = Self::check(load)?;
+        Ok(())
     }
 
     pub fn get_load(&self) -> f32 {
         self.load
     }
+
+    /// Switching the reactor off shuts down fission regardless of the
+    /// fission rate.
+    pub fn set_powered(&mut self, powered: bool) {
+        self.powered = powered;
+    }
+
+    pub fn is_powered(&self) -> bool {
+        self.powered
+    }
+
+    /// Hands `fuel_rod` to the reactor, it replaces the most depleted rod on
+    /// the next update.
+    pub fn insert_fuel_rod(&mut self, fuel_rod: FuelRod) {
+        self.fuel_rod_inserts.push(fuel_rod);
+    }
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Output {
     temperature: f32,
+    temperature_rate: f32,
     load: f32,
     power: f32,
     fuel_potential: f32,
     fission_rate: f32,
     turbine_rate: f32,
+    flooding: f32,
+    net_heat: f32,
+    fuel_remaining: f32,
+    fuel_rod_ceiling: f32,
+    fission_target: f32,
+    coolant_factor: f32,
+    condition: f32,
+    turbine_condition: f32,
+    voltage: f32,
+    melted_down: bool,
 }
 
 impl Output {
     fn new() -> Self {
         Self {
             temperature: 0.0,
+            temperature_rate: 0.0,
             load: 0.0,
             power: 0.0,
             fuel_potential: 0.0,
             fission_rate: 0.0,
             turbine_rate: 0.0,
+            flooding: 0.0,
+            net_heat: 0.0,
+            fuel_remaining: 0.0,
+            fuel_rod_ceiling: 0.0,
+            fission_target: 0.0,
+            coolant_factor: 0.0,
+            condition: 0.0,
+            turbine_condition: 0.0,
+            voltage: 0.0,
+            melted_down: false,
         }
     }
 
@@ -69,6 +223,11 @@ impl Output {
         self.temperature
     }
 
+    /// Change of the temperature over the last tick, per second.
+    pub fn get_temperature_rate(&self) -> f32 {
+        self.temperature_rate
+    }
+
     pub fn get_load(&self) -> f32 {
         self.load
     }
@@ -81,39 +240,240 @@ impl Output {
         self.fuel_potential
     }
 
-    /// Hidden, cannot read ingame
+    /// Output with the given readings and everything else zeroed, to call a
+    /// controller without running a reactor.
+    pub fn for_test(temperature: f32, load: f32, power: f32) -> Self {
+        Self {
+            temperature,
+            load,
+            power,
+            ..Self::new()
+        }
+    }
+
+    pub(crate) fn perturbed(&self, temperature: f32, load: f32) -> Output {
+        let mut output = self.clone();
+        output.temperature += temperature;
+        output.load += load;
+        output
+    }
+
+    /// Hidden, cannot read ingame, see `Output::visible`
     pub fn get_fission_rate(&self) -> f32 {
         self.fission_rate
     }
 
-    /// Hidden, cannot read ingame
+    /// Hidden, cannot read ingame, see `Output::visible`
     pub fn get_turbine_rate(&self) -> f32 {
         self.turbine_rate
     }
+
+    pub fn get_flooding(&self) -> f32 {
+        self.flooding
+    }
+
+    pub fn get_fuel_remaining(&self) -> f32 {
+        self.fuel_remaining
+    }
+
+    /// Highest fission rate the fuel can sustain.
+    pub fn get_fuel_rod_ceiling(&self) -> f32 {
+        self.fuel_rod_ceiling
+    }
+
+    /// Fission rate the core is heading to, commanding more than the fuel
+    /// rod ceiling does not raise it. Hidden, cannot read ingame, see
+    /// `Output::visible`.
+    pub fn get_fission_target(&self) -> f32 {
+        self.fission_target
+    }
+
+    pub fn get_coolant_factor(&self) -> f32 {
+        self.coolant_factor
+    }
+
+    pub fn get_condition(&self) -> f32 {
+        self.condition
+    }
+
+    pub fn get_turbine_condition(&self) -> f32 {
+        self.turbine_condition
+    }
+
+    /// Power over load, 1.0 is matched.
+    pub fn get_voltage(&self) -> f32 {
+        self.voltage
+    }
+
+    pub fn is_melted_down(&self) -> bool {
+        self.melted_down
+    }
+
+    /// Temperature the reactor is heading towards.
+    pub fn get_net_heat(&self) -> f32 {
+        self.net_heat
+    }
 }
 
+/// Turbine efficiency as a function of temperature. Full efficiency inside
+/// the optimal band, falling off linearly to zero outside of it.
+#[derive(Debug, Clone, Copy)]
+pub struct EfficiencyCurve {
+    optimal_min: f32,
+    optimal_max: f32,
+    falloff: f32,
+}
+
+impl EfficiencyCurve {
+    pub fn new(optimal_min: f32, optimal_max: f32, falloff: f32) -> Self {
+        Self {
+            optimal_min,
+            optimal_max,
+            falloff,
+        }
+    }
+
+    pub fn efficiency(&self, temperature: f32) -> f32 {
+        let distance = if temperature < self.optimal_min {
+            self.optimal_min - temperature
+        } else if temperature > self.optimal_max {
+            temperature - self.optimal_max
+        } else {
+            0.0
+        };
+
+        if self.falloff <= 0.0 {
+            return if distance > 0.0 { 0.0 } else { 1.0 };
+        }
+
+        (1.0 - distance / self.falloff).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for EfficiencyCurve {
+    fn default() -> Self {
+        Self::new(4000.0, 6000.0, 4000.0)
+    }
+}
+
+/// Precision of the values shown by the in-game reactor interface.
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayPrecision {
+    rate_step: f32,
+    temperature_step: f32,
+}
+
+impl DisplayPrecision {
+    pub fn new(rate_step: f32, temperature_step: f32) -> Self {
+        Self {
+            rate_step,
+            temperature_step,
+        }
+    }
+
+    fn round(value: f32, step: f32) -> f32 {
+        if step > 0.0 {
+            (value / step).round() * step
+        } else {
+            value
+        }
+    }
+
+    fn apply(&self, output: &mut Output) {
+        output.temperature = Self::round(output.temperature, self.temperature_step);
+        output.load = Self::round(output.load, self.rate_step);
+        output.power = Self::round(output.power, self.rate_step);
+        output.fission_rate = Self::round(output.fission_rate, self.rate_step);
+        output.turbine_rate = Self::round(output.turbine_rate, self.rate_step);
+    }
+}
+
+impl Default for DisplayPrecision {
+    /// Whole numbers, as displayed in game.
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+/// Default cap on the heat the fuel can provide to the core.
+pub const DEFAULT_HEAT_POTENTIAL: f32 = 320.0;
+
+/// Default maximum temperature change per second, for a thermal mass of 1.0.
+pub const DEFAULT_TEMPERATURE_RATE: f32 = 1000.0;
+
+/// Fraction of its maximum the turbine wears above.
+const TURBINE_OVERSPEED: f32 = 0.99;
+
+/// Additional turbine heat removal when the room is fully flooded.
+const FLOOD_COOLING: f32 = 2.0;
+
+#[derive(Clone)]
 pub struct Reactor {
-    fuel_potential: f32,
+    fuel_rods: Vec<FuelRod>,
+    /// Durability consumed from each rod per second at 100% fission.
+    fuel_consumption: f32,
     power_max: f32,
+    heat_potential: f32,
+    efficiency: EfficiencyCurve,
+    /// Heat in transit through the coolant loop, oldest first.
+    heat_transport: VecDeque<f32>,
+    /// Seconds heat spends in transit through the coolant loop.
+    heat_transport_delay: f32,
     turbine: Turbine,
     core: Core,
-    load: f32,
     input: Input,
     temperature: f32,
+    flooding: f32,
+    coolant_factor: f32,
+    heating_rate: f32,
+    cooling_rate: f32,
+    /// Divides the rate of temperature change, 1.0 is the game's reactor.
+    thermal_mass: f32,
+    ambient_temperature: f32,
+    /// Degrees per second lost per degree above `ambient_temperature`.
+    passive_cooling: f32,
+    integrator: IntegratorKind,
+    display_precision: Option<DisplayPrecision>,
+    safety_limits: SafetyLimits,
+    /// Seconds above the meltdown temperature before the reactor melts down.
+    meltdown_delay: f32,
+    critical_time: f32,
+    melted_down: bool,
+    /// Health of the reactor in percent, scales the maximum power.
+    condition: f32,
+    damage_temperature: f32,
+    /// Condition lost per second per degree above `damage_temperature`.
+    damage_rate: f32,
+    /// Health of the turbine in percent, caps the turbine rate.
+    turbine_condition: f32,
+    /// Turbine condition lost per second at overspeed, scaled by how far the
+    /// heat supply exceeds the heat demand, relative to the demand.
+    turbine_wear_rate: f32,
+    /// Voltage band (undervolt, overvolt) considered stable.
+    voltage_limits: (f32, f32),
+    /// Core value and seconds above it before heat is produced, `None` when
+    /// the reactor produces heat right away.
+    ignition: Option<(f32, f32)>,
+    ignition_time: f32,
+    ignited: bool,
+    /// Whether the turbine matches power to the load on its own.
+    auto_turbine: bool,
     output: Output,
 }
 
 impl Debug for Reactor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Reactor")
-            .field("turbine", &self.turbine.value)
-            .field("turbine_target", &self.turbine.target)
-            .field("fission", &self.core.value)
-            .field("fission_target", &self.core.target)
-            .field("fuel_potential", &self.fuel_potential)
+            .field("turbine", &self.turbine.get_value())
+            .field("turbine_target", &self.turbine.get_target())
+            .field("fission", &self.core.get_value())
+            .field("fission_target", &self.core.get_target())
+            .field("fuel_rods", &self.fuel_rods)
             .field("power_max", &self.power_max)
-            .field("load", &self.load)
             .field("temperatur", &self.temperature)
+            .field("melted_down", &self.melted_down)
+            .field("condition", &self.condition)
+            .field("turbine_condition", &self.turbine_condition)
             .finish()
     }
 }
@@ -121,22 +481,239 @@ impl Debug for Reactor {
 impl Reactor {
 
     pub fn new(fuel_potential: f32, power_max: f32) -> Self {
+        ReactorBuilder::new()
+            .fuel_potential(fuel_potential)
+            .power_max(power_max)
+            .build()
+    }
+
+    pub fn builder() -> ReactorBuilder {
+        ReactorBuilder::new()
+    }
+
+    pub fn with_fuel_rod_type(fuel_rod_type: FuelRodType, power_max: f32) -> Self {
+        Self::with_fuel_rods(vec![fuel_rod_type.rod()], power_max)
+    }
+
+    /// Reactor with several fuel rods, the fuel potential is the sum of the
+    /// rods that are not depleted yet.
+    pub fn with_fuel_rods(fuel_rods: Vec<FuelRod>, power_max: f32) -> Self {
         Self {
             input: Input::new(),
             core: Core::new(),
             turbine: Turbine::new(),
             power_max,
-            fuel_potential,
-            load: 0.0,
+            heat_potential: DEFAULT_HEAT_POTENTIAL,
+            fuel_rods,
+            fuel_consumption: 0.1,
+            efficiency: EfficiencyCurve::default(),
+            heat_transport: VecDeque::new(),
+            heat_transport_delay: 0.0,
             temperature: 0.0,
+            flooding: 0.0,
+            coolant_factor: 1.0,
+            heating_rate: DEFAULT_TEMPERATURE_RATE,
+            cooling_rate: DEFAULT_TEMPERATURE_RATE,
+            thermal_mass: 1.0,
+            ambient_temperature: 0.0,
+            passive_cooling: 0.0,
+            integrator: IntegratorKind::default(),
+            display_precision: None,
+            safety_limits: SafetyLimits::default(),
+            meltdown_delay: 5.0,
+            critical_time: 0.0,
+            melted_down: false,
+            condition: 100.0,
+            damage_temperature: 6000.0,
+            damage_rate: 0.001,
+            turbine_condition: 100.0,
+            turbine_wear_rate: 0.01,
+            voltage_limits: (0.9, 1.1),
+            ignition: None,
+            ignition_time: 0.0,
+            ignited: true,
+            auto_turbine: false,
             output: Output::new(),
         }
     }
 
+    /// Round the values reported in `Output`, the internal state stays exact.
+    pub fn with_display_precision(mut self, precision: DisplayPrecision) -> Self {
+        self.display_precision = Some(precision);
+        self
+    }
+
+    /// Maximum temperature change per second when heating up and cooling down.
+    pub fn with_temperature_rates(mut self, heating_rate: f32, cooling_rate: f32) -> Self {
+        self.heating_rate = heating_rate;
+        self.cooling_rate = cooling_rate;
+        self
+    }
+
+    /// Fuel consumed per second at 100% fission, out of 100.
+    pub fn with_fuel_consumption(mut self, fuel_consumption: f32) -> Self {
+        self.fuel_consumption = fuel_consumption.max(0.0);
+        self
+    }
+
+    pub fn get_fuel_consumption(&self) -> f32 {
+        self.fuel_consumption
+    }
+
+    /// Melt down once the temperature stays above `temperature` for `delay`
+    /// seconds.
+    pub fn with_meltdown(mut self, temperature: f32, delay: f32) -> Self {
+        self.safety_limits.set_meltdown_temperature(temperature);
+        self.meltdown_delay = delay;
+        self
+    }
+
+    pub fn get_meltdown_temperature(&self) -> f32 {
+        self.safety_limits.get_meltdown_temperature()
+    }
+
+    pub fn with_safety_limits(mut self, safety_limits: SafetyLimits) -> Self {
+        self.safety_limits = safety_limits;
+        self
+    }
+
+    pub fn get_safety_limits(&self) -> SafetyLimits {
+        self.safety_limits
+    }
+
+    /// Whether the temperature is at or below the critical temperature.
+    pub fn is_safe(&self) -> bool {
+        self.temperature <= self.safety_limits.get_critical_temperature()
+    }
+
+    pub fn get_meltdown_delay(&self) -> f32 {
+        self.meltdown_delay
+    }
+
+    /// Lose `rate` condition per second per degree above `temperature`.
+    pub fn with_damage(mut self, temperature: f32, rate: f32) -> Self {
+        self.damage_temperature = temperature;
+        self.damage_rate = rate;
+        self
+    }
+
+    /// Lose `rate` turbine condition per second while the turbine is pinned
+    /// at its maximum with more heat supplied than it demands.
+    pub fn with_turbine_wear(mut self, rate: f32) -> Self {
+        self.turbine_wear_rate = rate.max(0.0);
+        self
+    }
+
+    pub fn with_heat_potential(mut self, heat_potential: f32) -> Self {
+        self.heat_potential = heat_potential;
+        self
+    }
+
+    pub fn get_heat_potential(&self) -> f32 {
+        self.heat_potential
+    }
+
+    /// Voltages below `undervolt` or above `overvolt` are unstable.
+    pub fn with_voltage_limits(mut self, undervolt: f32, overvolt: f32) -> Self {
+        self.voltage_limits = (undervolt, overvolt);
+        self
+    }
+
+    pub fn get_voltage_limits(&self) -> (f32, f32) {
+        self.voltage_limits
+    }
+
+    /// Cold start: the core only produces heat once its value stayed above
+    /// `threshold` for `warmup` seconds, and stops again when it falls below.
+    pub fn with_ignition(mut self, threshold: f32, warmup: f32) -> Self {
+        self.ignition = Some((threshold, warmup.max(0.0)));
+        self.ignition_time = 0.0;
+        self.ignited = false;
+        self
+    }
+
+    pub fn get_ignition(&self) -> Option<(f32, f32)> {
+        self.ignition
+    }
+
+    /// Whether the core produces heat, always true without an ignition model.
+    pub fn is_ignited(&self) -> bool {
+        self.ignited
+    }
+
+    pub fn get_heating_rate(&self) -> f32 {
+        self.heating_rate
+    }
+
+    pub fn get_cooling_rate(&self) -> f32 {
+        self.cooling_rate
+    }
+
+    /// Larger reactors respond slower, the heating, cooling and passive
+    /// cooling rates are divided by `thermal_mass`.
+    pub fn with_thermal_mass(mut self, thermal_mass: f32) -> Self {
+        self.thermal_mass = thermal_mass.max(f32::EPSILON);
+        self
+    }
+
+    pub fn get_thermal_mass(&self) -> f32 {
+        self.thermal_mass
+    }
+
+    pub fn get_ambient_temperature(&self) -> f32 {
+        self.ambient_temperature
+    }
+
+    pub fn get_passive_cooling(&self) -> f32 {
+        self.passive_cooling
+    }
+
+    /// Fresh reactor with the same configuration, but none of the state.
+    pub(crate) fn probe(&self) -> Reactor {
+        let mut reactor = Reactor::with_fuel_rods(self.fuel_rods.clone(), self.power_max);
+        reactor.fuel_consumption = self.fuel_consumption;
+        reactor.heat_potential = self.heat_potential;
+        reactor.efficiency = self.efficiency;
+        reactor.heat_transport_delay = self.heat_transport_delay;
+        reactor.flooding = self.flooding;
+        reactor.coolant_factor = self.coolant_factor;
+        reactor.heating_rate = self.heating_rate;
+        reactor.cooling_rate = self.cooling_rate;
+        reactor.thermal_mass = self.thermal_mass;
+        reactor.ambient_temperature = self.ambient_temperature;
+        reactor.passive_cooling = self.passive_cooling;
+        reactor.integrator = self.integrator;
+        reactor.safety_limits = self.safety_limits;
+        reactor.meltdown_delay = self.meltdown_delay;
+        reactor.condition = self.condition;
+        reactor.damage_temperature = self.damage_temperature;
+        reactor.damage_rate = self.damage_rate;
+        reactor.turbine_condition = self.turbine_condition;
+        reactor.turbine_wear_rate = self.turbine_wear_rate;
+        reactor.voltage_limits = self.voltage_limits;
+        reactor.auto_turbine = self.auto_turbine;
+        if let Some((threshold, warmup)) = self.ignition {
+            reactor = reactor.with_ignition(threshold, warmup);
+        }
+        reactor.core = Core::new()
+            .with_response(self.core.get_slew_rate(), self.core.get_time_constant());
+        reactor.turbine = Turbine::new()
+            .with_response(self.turbine.get_slew_rate(), self.turbine.get_time_constant());
+        reactor
+    }
+
     pub fn get_output(&self) -> &Output {
         &self.output
     }
 
+    pub fn get_core(&self) -> &Core {
+        &self.core
+    }
+
+    pub fn get_turbine(&self) -> &Turbine {
+        &self.turbine
+    }
+
     pub fn get_input(&self) -> &Input {
         &self.input
     }
@@ -145,122 +722,833 @@ impl Reactor {
         &mut self.input
     }
 
+    pub fn set_input(&mut self, input: Input) {
+        self.input = input;
+    }
+
+    pub fn set_integrator(&mut self, integrator: IntegratorKind) {
+        self.integrator = integrator;
+    }
+
+    pub fn get_integrator(&self) -> IntegratorKind {
+        self.integrator
+    }
+
+    pub fn set_efficiency_curve(&mut self, efficiency: EfficiencyCurve) {
+        self.efficiency = efficiency;
+    }
+
+    pub fn get_efficiency_curve(&self) -> &EfficiencyCurve {
+        &self.efficiency
+    }
+
+    /// Delay in seconds between heat being produced by the core and reaching
+    /// the turbine side. Resets any heat currently in transit.
+    pub fn set_heat_transport_delay(&mut self, delay: f32) {
+        self.heat_transport_delay = if delay.is_finite() {
+            delay.max(0.0)
+        } else {
+            0.0
+        };
+        self.heat_transport.clear();
+    }
+
+    pub fn get_heat_transport_delay(&self) -> f32 {
+        self.heat_transport_delay
+    }
+
+    /// Fraction of the reactor room under water, flooding boosts the heat
+    /// removed by the turbine.
+    pub fn set_room_flooded(&mut self, fraction: f32) {
+        self.flooding = if fraction.is_finite() {
+            fraction.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+    }
+
+    pub fn get_room_flooded(&self) -> f32 {
+        self.flooding
+    }
+
+    /// Scales the heat the turbine can remove, below 1.0 it can't shed heat
+    /// as fast.
+    pub fn set_coolant_factor(&mut self, coolant_factor: f32) {
+        self.coolant_factor = if coolant_factor.is_finite() {
+            coolant_factor.max(0.0)
+        } else {
+            0.0
+        };
+    }
+
+    pub fn get_coolant_factor(&self) -> f32 {
+        self.coolant_factor
+    }
+
     pub fn controls(&mut self) -> (&mut Input, &Output) {
         (&mut self.input, &self.output)
     }
 }
 
-struct Core {
-    value: f32,
-    target: f32,
+/// Dynamic state of a reactor, to branch simulations from a common point.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReactorState {
+    temperature: f32,
+    core_value: f32,
+    core_target: f32,
+    turbine_value: f32,
+    turbine_target: f32,
+    fuel_rods: Vec<FuelRod>,
+    heat_transport: Vec<f32>,
+    critical_time: f32,
+    melted_down: bool,
+    condition: f32,
+    turbine_condition: f32,
+    ignition_time: f32,
+    ignited: bool,
+    input: Input,
+    output: Output,
 }
 
-impl Core {
-    fn new() -> Self {
-        Self {
-            value: 0.0,
-            target: 0.0,
-        }
+impl ReactorState {
+    pub fn get_temperature(&self) -> f32 {
+        self.temperature
     }
 
-    fn update(&mut self, new_target: f32, time_delta: f32) {
-        self.target = if self.target >= new_target {
-            (self.target - time_delta * 5.0).max(new_target)
-        } else {
-            (self.target + time_delta * 5.0).min(new_target)
-        };
-        let heat_potential = 320.0;
+    pub fn get_condition(&self) -> f32 {
+        self.condition
+    }
 
-        self.value += (self.target.min(heat_potential) - self.value) * time_delta;
-        self.value = self.value.clamp(0.0, 100.0);
+    pub fn get_input(&self) -> &Input {
+        &self.input
     }
 }
 
-struct Turbine {
-    value: f32,
-    target: f32,
+impl Reactor {
+    pub fn snapshot(&self) -> ReactorState {
+        ReactorState {
+            temperature: self.temperature,
+            core_value: self.core.get_value(),
+            core_target: self.core.get_target(),
+            turbine_value: self.turbine.get_value(),
+            turbine_target: self.turbine.get_target(),
+            fuel_rods: self.fuel_rods.clone(),
+            heat_transport: self.heat_transport.iter().copied().collect(),
+            critical_time: self.critical_time,
+            melted_down: self.melted_down,
+            condition: self.condition,
+            turbine_condition: self.turbine_condition,
+            ignition_time: self.ignition_time,
+            ignited: self.ignited,
+            input: self.input.clone(),
+            output: self.output.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, state: &ReactorState) {
+        self.temperature = state.temperature;
+        self.core.set_state(state.core_value, state.core_target);
+        self.turbine.set_state(state.turbine_value, state.turbine_target);
+        self.fuel_rods = state.fuel_rods.clone();
+        self.heat_transport = state.heat_transport.iter().copied().collect();
+        self.critical_time = state.critical_time;
+        self.melted_down = state.melted_down;
+        self.condition = state.condition;
+        self.turbine_condition = state.turbine_condition;
+        self.ignition_time = state.ignition_time;
+        self.ignited = state.ignited;
+        self.input = state.input.clone();
+        self.output = state.output.clone();
+    }
 }
 
-impl Turbine {
-    fn new() -> Self {
+#[derive(Clone)]
+pub struct ReactorBuilder {
+    fuel_rods: Vec<FuelRod>,
+    power_max: f32,
+    heat_potential: f32,
+    coolant_factor: f32,
+    initial_temperature: f32,
+    core_response: (f32, f32),
+    turbine_response: (f32, f32),
+    ambient_temperature: f32,
+    passive_cooling: f32,
+    thermal_mass: f32,
+    auto_turbine: bool,
+}
+
+impl ReactorBuilder {
+    /// A single normal fuel rod and 4000 max power.
+    pub fn new() -> Self {
         Self {
-            value: 0.0,
-            target: 0.0,
+            fuel_rods: vec![FuelRodType::Normal.rod()],
+            power_max: 4000.0,
+            heat_potential: DEFAULT_HEAT_POTENTIAL,
+            coolant_factor: 1.0,
+            initial_temperature: 0.0,
+            core_response: (DEFAULT_SLEW_RATE, DEFAULT_TIME_CONSTANT),
+            turbine_response: (DEFAULT_SLEW_RATE, DEFAULT_TIME_CONSTANT),
+            ambient_temperature: 0.0,
+            passive_cooling: 0.0,
+            thermal_mass: 1.0,
+            auto_turbine: false,
         }
     }
 
-    fn update(&mut self, new_target: f32, time_delta: f32) {
-        self.target = if self.target >= new_target {
-            (self.target - time_delta * 5.0).max(new_target)
+    /// Replaces the fuel rods with a single fresh rod of `fuel_potential`.
+    pub fn fuel_potential(mut self, fuel_potential: f32) -> Self {
+        self.fuel_rods = vec![FuelRod::new(fuel_potential, 100.0)];
+        self
+    }
+
+    pub fn fuel_rods(mut self, fuel_rods: Vec<FuelRod>) -> Self {
+        self.fuel_rods = fuel_rods;
+        self
+    }
+
+    pub fn power_max(mut self, power_max: f32) -> Self {
+        self.power_max = power_max;
+        self
+    }
+
+    pub fn heat_potential(mut self, heat_potential: f32) -> Self {
+        self.heat_potential = heat_potential;
+        self
+    }
+
+    pub fn coolant_factor(mut self, coolant_factor: f32) -> Self {
+        self.coolant_factor = coolant_factor;
+        self
+    }
+
+    pub fn initial_temperature(mut self, initial_temperature: f32) -> Self {
+        self.initial_temperature = initial_temperature;
+        self
+    }
+
+    /// How fast the core follows the fission rate: the slew rate of its
+    /// target per second and the time constant in seconds of the lag behind it.
+    pub fn core_response(mut self, slew_rate: f32, time_constant: f32) -> Self {
+        self.core_response = (slew_rate, time_constant.max(f32::EPSILON));
+        self
+    }
+
+    /// Like `core_response`, for the turbine.
+    pub fn turbine_response(mut self, slew_rate: f32, time_constant: f32) -> Self {
+        self.turbine_response = (slew_rate, time_constant.max(f32::EPSILON));
+        self
+    }
+
+    pub fn ambient_temperature(mut self, ambient_temperature: f32) -> Self {
+        self.ambient_temperature = ambient_temperature;
+        self
+    }
+
+    /// Degrees per second lost per degree above the ambient temperature.
+    pub fn passive_cooling(mut self, passive_cooling: f32) -> Self {
+        self.passive_cooling = passive_cooling.max(0.0);
+        self
+    }
+
+    /// See `Reactor::with_thermal_mass`.
+    pub fn thermal_mass(mut self, thermal_mass: f32) -> Self {
+        self.thermal_mass = thermal_mass;
+        self
+    }
+
+    /// See `Reactor::set_auto_turbine`.
+    pub fn auto_turbine(mut self, auto_turbine: bool) -> Self {
+        self.auto_turbine = auto_turbine;
+        self
+    }
+
+    pub fn build(self) -> Reactor {
+        let mut reactor = Reactor::with_fuel_rods(self.fuel_rods, self.power_max)
+            .with_heat_potential(self.heat_potential)
+            .with_thermal_mass(self.thermal_mass);
+        reactor.set_coolant_factor(self.coolant_factor);
+        reactor.ambient_temperature = self.ambient_temperature;
+        reactor.passive_cooling = self.passive_cooling;
+        reactor.auto_turbine = self.auto_turbine;
+        reactor.temperature = if self.initial_temperature.is_finite() {
+            self.initial_temperature.clamp(0.0, MAX_TEMPERATURE)
         } else {
-            (self.target + time_delta * 5.0).min(new_target)
+            0.0
         };
-        self.value += (self.target - self.value) * time_delta;
-        self.value = self.value.clamp(0.0, 100.0);
+        reactor.output.temperature = reactor.temperature;
+        let (slew_rate, time_constant) = self.core_response;
+        reactor.core = Core::new().with_response(slew_rate, time_constant);
+        let (slew_rate, time_constant) = self.turbine_response;
+        reactor.turbine = Turbine::new().with_response(slew_rate, time_constant);
+        reactor
+    }
+}
+
+impl Default for ReactorBuilder {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Reactor {
     pub fn update(&mut self, time_delta: f32) {
+        debug_assert!(time_delta.is_finite(), "time delta is not finite");
+
+        for fuel_rod in std::mem::take(&mut self.input.fuel_rod_inserts) {
+            self.insert_fuel_rod(fuel_rod);
+        }
+
+        self.update_ignition(time_delta);
         self.update_temperatur(time_delta);
+        self.update_meltdown(time_delta);
+        self.update_condition(time_delta);
         
         // self.core.target = self.input.fission_rate;
-        self.core.update(self.input.fission_rate, time_delta);
+        let fission_rate = if self.input.powered {
+            self.input.fission_rate
+        } else {
+            0.0
+        };
+        self.core.update(
+            fission_rate,
+            self.max_fission_rate(),
+            time_delta,
+            &self.integrator,
+        );
 
         // self.turbine.target = self.input.turbine_rate;
-        self.turbine.update(self.input.turbine_rate, time_delta);
+        self.turbine.update(
+            self.turbine_command().min(self.turbine_condition),
+            time_delta,
+            &self.integrator,
+        );
+
+        let depletion = self.core.get_value() / 100.0 * self.fuel_consumption * time_delta;
+        for fuel_rod in &mut self.fuel_rods {
+            fuel_rod.deplete(depletion);
+        }
 
         // Update outputs
-        self.output.fuel_potential = self.fuel_potential;
+        self.output.fuel_potential = self.get_fuel_potential();
+        self.output.fuel_remaining = self.get_fuel_remaining();
+        self.output.fuel_rod_ceiling = self.max_fission_rate();
         self.output.fission_rate = self.get_fission_rate();
+        self.output.fission_target = self.get_fission_target();
         self.output.load = self.input.get_load();
         self.output.turbine_rate = self.get_turbine_rate();
+        self.output.power = self.get_power();
+        self.output.flooding = self.flooding;
+        self.output.coolant_factor = self.coolant_factor;
+        self.output.condition = self.condition;
+        self.output.turbine_condition = self.turbine_condition;
+        self.output.voltage = self.get_voltage();
+        self.output.melted_down = self.melted_down;
+
+        if let Some(precision) = &self.display_precision {
+            precision.apply(&mut self.output);
+        }
+
+        debug_assert!(self.is_finite(), "reactor state is not finite: {self:?}");
+    }
+
+    fn is_finite(&self) -> bool {
+        [
+            self.temperature,
+            self.core.get_value(),
+            self.core.get_target(),
+            self.turbine.get_value(),
+            self.turbine.get_target(),
+            self.condition,
+            self.turbine_condition,
+        ]
+        .iter()
+        .all(|value| value.is_finite())
+    }
+
+    fn update_ignition(&mut self, time_delta: f32) {
+        let Some((threshold, warmup)) = self.ignition else {
+            return;
+        };
+        if self.core.get_value() > threshold {
+            self.ignition_time += time_delta;
+        } else {
+            self.ignition_time = 0.0;
+        }
+        self.ignited = self.ignition_time > 0.0 && self.ignition_time >= warmup;
     }
 
     fn update_temperatur(&mut self, time_delta: f32) {
-        let heat_supply = self.heat_supply();
+        // Heat stays in transit for as many ticks as the delay spans, nothing
+        // arrives until the loop has filled up
+        let in_transit = (self.heat_transport_delay / time_delta).round() as usize;
+        self.heat_transport.push_back(self.heat_supply());
+        let mut heat_supply = 0.0;
+        while self.heat_transport.len() > in_transit {
+            heat_supply = self.heat_transport.pop_front().unwrap_or_default();
+        }
 
-        let temperatur_delta = (heat_supply - self.turbine.value * 100.0) - self.temperature;
-        
-        self.temperature += (temperatur_delta.signum() * 1000.0 * time_delta).clamp(-temperatur_delta.abs(), temperatur_delta.abs());
-        self.temperature = self.temperature.clamp(0.0, 10000.0);
+        let net_heat = heat_supply - self.heat_removal();
+        self.output.net_heat = net_heat;
+
+        let (heating_rate, cooling_rate) = (
+            self.heating_rate / self.thermal_mass,
+            self.cooling_rate / self.thermal_mass,
+        );
+        let passive_cooling = self.passive_cooling / self.thermal_mass;
+        let ambient = self.ambient_temperature;
+        let derivative = |temperature: f32| {
+            // Nothing cools the reactor below the ambient temperature
+            let temperatur_delta = net_heat.max(ambient) - temperature;
+            let rate = if temperatur_delta >= 0.0 {
+                heating_rate
+            } else {
+                cooling_rate
+            };
+            // Never step past the target within a tick
+            let max_rate = if time_delta > 0.0 {
+                temperatur_delta.abs() / time_delta
+            } else {
+                f32::INFINITY
+            };
+            (temperatur_delta.signum() * rate).clamp(-max_rate, max_rate)
+                - passive_cooling * (temperature - ambient)
+        };
+
+        let previous = self.temperature;
+        self.temperature = self.integrator.step(self.temperature, time_delta, &derivative);
+        self.temperature = self.temperature.max(ambient).clamp(0.0, MAX_TEMPERATURE);
 
         self.output.temperature = self.temperature;
+        self.output.temperature_rate = if time_delta > 0.0 {
+            (self.temperature - previous) / time_delta
+        } else {
+            0.0
+        };
+    }
+
+    /// With auto turbine on and a load set, the turbine matches power to the
+    /// load and controllers only manage fission. Otherwise the turbine
+    /// follows the turbine rate of the input. Off by default.
+    pub fn set_auto_turbine(&mut self, auto_turbine: bool) {
+        self.auto_turbine = auto_turbine;
+    }
+
+    pub fn is_auto_turbine(&self) -> bool {
+        self.auto_turbine
+    }
+
+    /// Turbine rate the turbine ramps towards.
+    fn turbine_command(&self) -> f32 {
+        if self.auto_turbine && self.input.load > 0.0 {
+            self.turbine_rate_for_load(self.input.load)
+        } else {
+            self.input.turbine_rate
+        }
+    }
+
+    /// Power delivered relative to the load demand, 1.0 is matched. Without a
+    /// load there is nothing to mismatch and the voltage is 1.0.
+    pub fn get_voltage(&self) -> f32 {
+        let demand = self.input.load / 100.0 * self.power_max;
+        if demand <= 0.0 {
+            return 1.0;
+        }
+        self.get_power() / demand
+    }
+
+    pub fn is_overvolting(&self) -> bool {
+        self.get_voltage() > self.voltage_limits.1
+    }
+
+    pub fn is_undervolting(&self) -> bool {
+        self.get_voltage() < self.voltage_limits.0
+    }
+
+    /// Turbine rate delivering `load` percent of the max power at the current
+    /// efficiency and condition. Falls back to `load` while even a full
+    /// turbine can't deliver it, e.g. when cold, so the core can heat up.
+    pub fn turbine_rate_for_load(&self, load: f32) -> f32 {
+        let factor = self.get_turbine_efficiency() * self.condition / 100.0;
+        if factor * 100.0 < load {
+            return load.clamp(0.0, 100.0);
+        }
+        (load / factor).clamp(0.0, 100.0)
+    }
+
+    fn update_meltdown(&mut self, time_delta: f32) {
+        if self.temperature > self.safety_limits.get_meltdown_temperature() {
+            self.critical_time += time_delta;
+        } else {
+            self.critical_time = 0.0;
+        }
+
+        if self.critical_time >= self.meltdown_delay {
+            self.melted_down = true;
+        }
+    }
+
+    fn update_condition(&mut self, time_delta: f32) {
+        let excess = (self.temperature - self.damage_temperature).max(0.0);
+        self.add_condition(-excess * self.damage_rate * time_delta);
+
+        let demand = self.heat_demand();
+        if self.turbine.get_value() >= TURBINE_OVERSPEED * self.turbine_condition && demand > 0.0
+        {
+            let excess = (self.heat_supply() / demand - 1.0).max(0.0);
+            self.turbine_condition = (self.turbine_condition
+                - excess * self.turbine_wear_rate * time_delta)
+                .clamp(0.0, 100.0);
+        }
+    }
+
+    pub fn is_melted_down(&self) -> bool {
+        self.melted_down
+    }
+
+    pub fn set_powered(&mut self, powered: bool) {
+        self.input.set_powered(powered);
+    }
+
+    pub fn is_powered(&self) -> bool {
+        self.input.is_powered()
+    }
+
+    pub fn get_condition(&self) -> f32 {
+        self.condition
+    }
+
+    /// Repairs (or damages, when negative) the reactor.
+    pub fn add_condition(&mut self, condition: f32) {
+        self.condition = (self.condition + condition).clamp(0.0, 100.0);
+    }
+
+    /// Health of the turbine in percent, the turbine rate can't exceed it.
+    pub fn get_turbine_condition(&self) -> f32 {
+        self.turbine_condition
+    }
+
+    /// Repairs (or wears, when negative) the turbine.
+    pub fn add_turbine_condition(&mut self, condition: f32) {
+        self.turbine_condition = (self.turbine_condition + condition).clamp(0.0, 100.0);
     }
 
     pub fn heat_demand(&self) -> f32 {
-        self.turbine.value * 75.0
+        self.turbine.get_value() * 75.0
     }
 
     pub fn heat_supply(&self) -> f32 {
-        2.0 * self.core.value * self.fuel_potential
+        if self.melted_down || !self.ignited {
+            return 0.0;
+        }
+        2.0 * self.core.get_value() * self.get_fuel_potential()
+    }
+
+    /// Heat supply minus heat removal, the temperature the reactor settles at.
+    pub fn net_heat(&self) -> f32 {
+        self.heat_supply() - self.heat_removal()
+    }
+
+    pub fn heat_removal(&self) -> f32 {
+        self.turbine.get_value() * 100.0 * self.coolant_factor * (1.0 + self.flooding * FLOOD_COOLING)
+    }
+
+    /// Heat the core supplies once settled at a commanded `fission` rate.
+    /// Linear at `2 * fission * fuel_potential` up to `max_fission_rate` and
+    /// flat beyond it, zero while the core produces no heat at all.
+    pub fn heat_for_fission(&self, fission: f32) -> f32 {
+        if self.melted_down || !self.ignited {
+            return 0.0;
+        }
+        2.0 * fission.clamp(0.0, self.max_fission_rate()) * self.get_fuel_potential()
+    }
+
+    /// Inverse of `heat_for_fission`, heat beyond what the fuel can supply
+    /// maps to `max_fission_rate`.
+    pub fn fission_for_heat(&self, heat: f32) -> f32 {
+        let fuel_potential = self.get_fuel_potential();
+        if fuel_potential <= 0.0 {
+            return 0.0;
+        }
+        (heat / (2.0 * fuel_potential)).clamp(0.0, self.max_fission_rate())
+    }
+
+    /// Fission rate at which the temperature settles at `temperature` for the
+    /// current turbine rate.
+    pub fn steady_state_fission(&self, temperature: f32) -> f32 {
+        self.fission_for_heat(temperature + self.heat_removal())
+    }
+
+    /// Highest fission rate that does not eventually overheat the reactor at
+    /// the current turbine rate.
+    pub fn max_safe_fission(&self) -> f32 {
+        self.steady_state_fission(self.safety_limits.get_critical_temperature())
     }
 
     pub fn get_temperature(&self) -> f32 {
         self.temperature
     }
 
+    /// Sum of the potential of all rods that are not depleted.
+    pub fn get_fuel_potential(&self) -> f32 {
+        self.fuel_rods
+            .iter()
+            .filter(|fuel_rod| !fuel_rod.is_depleted())
+            .map(FuelRod::get_potential)
+            .sum()
+    }
+
+    /// Total durability left across all rods.
+    pub fn get_fuel_remaining(&self) -> f32 {
+        self.fuel_rods.iter().map(FuelRod::get_durability).sum()
+    }
+
+    pub fn get_fuel_rods(&self) -> &[FuelRod] {
+        &self.fuel_rods
+    }
+
+    /// Swaps `fuel_rod` in for the most depleted rod, the durability left in
+    /// that rod is lost. Only a reactor without rods gains a rod.
+    pub fn insert_fuel_rod(&mut self, fuel_rod: FuelRod) {
+        let most_depleted = self
+            .fuel_rods
+            .iter_mut()
+            .min_by(|a, b| a.get_durability().total_cmp(&b.get_durability()));
+        match most_depleted {
+            Some(rod) => *rod = fuel_rod,
+            None => self.fuel_rods.push(fuel_rod),
+        }
+    }
+
+    /// Highest fission rate the fuel can sustain, commanding more than this
+    /// has no effect.
+    pub fn max_fission_rate(&self) -> f32 {
+        let fuel_potential = self.get_fuel_potential();
+        if fuel_potential <= 0.0 {
+            return 0.0;
+        }
+        (self.heat_potential / fuel_potential * 100.0).clamp(0.0, 100.0)
+    }
+
     pub fn get_fission_rate(&self) -> f32 {
-        self.core.value
+        self.core.get_value()
+    }
+
+    /// Fission rate the core is heading to, the commanded rate capped by
+    /// `max_fission_rate`.
+    pub fn get_fission_target(&self) -> f32 {
+        self.core.get_target()
     }
 
     pub fn get_turbine_rate(&self) -> f32 {
-        self.turbine.value
+        self.turbine.get_value()
     }
 
     pub fn set_fission_rate(&mut self, fission_rate: f32) {
-        self.input.fission_rate = fission_rate.clamp(0.0, 100.0);
+        self.input.set_fission_rate(fission_rate);
     }
 
     pub fn set_turbine_rate(&mut self, turbine_rate: f32) {
-        self.input.turbine_rate = turbine_rate.clamp(0.0, 100.0);
+        self.input.set_turbine_rate(turbine_rate);
     }
 
     pub fn set_load(&mut self, load: f32) {
-        self.load = load.max(0.0);
+        self.input.set_load(load);
+    }
+
+    pub fn get_turbine_efficiency(&self) -> f32 {
+        self.efficiency.efficiency(self.temperature)
+    }
+
+    /// Fraction of the heat demanded by the turbine the core supplies, the
+    /// turbine cannot turn heat it does not get into power.
+    pub fn get_heat_availability(&self) -> f32 {
+        let demand = self.heat_demand();
+        if demand <= 0.0 {
+            return 1.0;
+        }
+        (self.heat_supply() / demand).clamp(0.0, 1.0)
     }
 
     pub fn get_power(&self) -> f32 {
-        self.turbine.value * self.power_max / 100.0
+        if self.melted_down {
+            return 0.0;
+        }
+        self.turbine.get_value() * self.power_max / 100.0
+            * self.get_turbine_efficiency()
+            * self.get_heat_availability()
+            * self.condition
+            / 100.0
+    }
+
+    pub fn get_power_max(&self) -> f32 {
+        self.power_max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIME_DELTA: f32 = 1.0 / 60.0;
+
+    fn reactor(fission_rate: f32, turbine_rate: f32) -> Reactor {
+        let mut reactor = Reactor::new(320.0, 4000.0);
+        reactor.get_input_mut().set_fission_rate(fission_rate);
+        reactor.get_input_mut().set_turbine_rate(turbine_rate);
+        reactor
+    }
+
+    #[test]
+    fn low_coolant_factor_heats_faster() {
+        let mut normal = reactor(15.0, 50.0);
+        let mut flooded = reactor(15.0, 50.0);
+        flooded.set_coolant_factor(0.5);
+        for _ in 0..600 {
+            normal.update(TIME_DELTA);
+            flooded.update(TIME_DELTA);
+            assert!(flooded.get_temperature() >= normal.get_temperature());
+        }
+        assert!(flooded.get_temperature() > normal.get_temperature());
+    }
+
+    #[test]
+    fn rising_load_crosses_from_overvolt_to_undervolt() {
+        let mut reactor = reactor(15.0, 50.0);
+        for _ in 0..3600 {
+            reactor.update(TIME_DELTA);
+        }
+        let mut readings = Vec::new();
+        for load in 1..=100 {
+            reactor.get_input_mut().set_load(load as f32);
+            reactor.update(TIME_DELTA);
+            readings.push((reactor.is_overvolting(), reactor.is_undervolting()));
+        }
+        assert_eq!(readings.first(), Some(&(true, false)));
+        assert_eq!(readings.last(), Some(&(false, true)));
+        let crossed = readings.iter().position(|&(_, under)| under).unwrap();
+        assert!(readings[crossed..].iter().all(|&(over, _)| !over));
+    }
+
+    #[test]
+    fn powered_off_reactor_shuts_down() {
+        let mut reactor = Reactor::builder().initial_temperature(5000.0).build();
+        reactor.get_input_mut().set_fission_rate(15.0);
+        reactor.get_input_mut().set_turbine_rate(50.0);
+        reactor.set_powered(false);
+        for _ in 0..3600 {
+            reactor.update(TIME_DELTA);
+        }
+        assert!(reactor.get_temperature() < 1.0);
+        assert!(reactor.get_fission_rate() < 1.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn steady_heating_has_constant_rate() {
+        let mut reactor = reactor(15.0, 50.0);
+        for _ in 0..3600 {
+            reactor.update(TIME_DELTA);
+        }
+        assert!(reactor.get_output().get_temperature_rate().abs() < 1.0);
+
+        reactor.get_input_mut().set_fission_rate(20.0);
+        for tick in 0..120 {
+            reactor.update(TIME_DELTA);
+            if tick >= 60 {
+                let rate = reactor.get_output().get_temperature_rate();
+                assert!((rate - reactor.get_heating_rate()).abs() < 10.0, "rate {rate}");
+            }
+        }
+    }
+
+    #[test]
+    fn non_finite_settings_fall_back_to_zero() {
+        let mut reactor = Reactor::builder().initial_temperature(f32::NAN).build();
+        reactor.set_room_flooded(f32::NAN);
+        reactor.set_coolant_factor(f32::INFINITY);
+        reactor.update(TIME_DELTA);
+        assert_eq!(reactor.get_temperature(), 0.0);
+        assert_eq!(reactor.get_room_flooded(), 0.0);
+        assert_eq!(reactor.get_coolant_factor(), 0.0);
+    }
+
+    #[test]
+    fn heat_transport_delay_is_independent_of_tick_rate() {
+        let arrival = |tick_rate: f32| {
+            let mut reactor = reactor(50.0, 0.0);
+            reactor.set_heat_transport_delay(1.0);
+            let ticks = (1..)
+                .find(|_| {
+                    reactor.update(1.0 / tick_rate);
+                    reactor.get_temperature() > 0.0
+                })
+                .unwrap();
+            ticks as f32 / tick_rate
+        };
+        for tick_rate in [30.0, 60.0, 120.0] {
+            let time = arrival(tick_rate);
+            assert!(time > 1.0 && time <= 1.0 + 2.0 / tick_rate, "{time}");
+        }
+    }
+
+    #[test]
+    fn zero_time_delta_keeps_state() {
+        let mut reactor = Reactor::new(320.0, 4000.0);
+        reactor.update(0.0);
+        assert_eq!(reactor.get_temperature(), 0.0);
+    }
+
+    #[test]
+    fn inserted_rod_replaces_the_most_depleted() {
+        let mut reactor = Reactor::with_fuel_rods(
+            vec![FuelRod::new(80.0, 60.0), FuelRod::new(80.0, 30.0)],
+            4000.0,
+        );
+        reactor.get_input_mut().insert_fuel_rod(FuelRod::new(80.0, 100.0));
+        reactor.update(TIME_DELTA);
+        let durability: Vec<f32> = reactor
+            .get_fuel_rods()
+            .iter()
+            .map(FuelRod::get_durability)
+            .collect();
+        assert_eq!(durability, [60.0, 100.0]);
+    }
+
+    #[test]
+    fn set_load_drives_the_input() {
+        let mut reactor = Reactor::new(320.0, 4000.0);
+        reactor.set_load(50.0);
+        assert_eq!(reactor.get_input().get_load(), 50.0);
+        assert!(reactor.is_undervolting());
+    }
+
+    #[test]
+    fn cold_start_under_load_heats_up() {
+        let mut reactor = Reactor::builder()
+            .fuel_potential(80.0)
+            .auto_turbine(true)
+            .build();
+        reactor.get_input_mut().set_fission_rate(50.0);
+        reactor.set_load(50.0);
+        for _ in 0..36000 {
+            reactor.update(TIME_DELTA);
+        }
+        assert!(reactor.get_temperature() > 1000.0);
+        assert!(reactor.get_power() > 0.0);
+        assert!(reactor.get_turbine_rate() < 100.0);
+    }
+
+    #[test]
+    fn turbine_follows_the_input_unless_auto() {
+        let run = |auto_turbine: bool| {
+            let mut reactor = reactor(15.0, 30.0);
+            reactor.set_auto_turbine(auto_turbine);
+            reactor.set_load(50.0);
+            for _ in 0..3600 {
+                reactor.update(TIME_DELTA);
+            }
+            reactor.get_turbine_rate()
+        };
+        assert!((run(false) - 30.0).abs() < 0.1);
+        assert!((run(true) - 30.0).abs() > 1.0);
+    }
+}
@@ -0,0 +1,143 @@
+use std::io::{self, Write};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{Controller, Input, Output};
+
+#[derive(Serialize)]
+struct Record<'a> {
+    tick: u64,
+    output: &'a Output,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input: Option<&'a Input>,
+}
+
+/// Streams the reactor state as JSON lines while the simulation runs.
+pub struct StreamingRecorder<W> {
+    writer: W,
+    every: u64,
+    tick: u64,
+    error: Option<io::Error>,
+}
+
+impl<W> StreamingRecorder<W>
+where
+    W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            every: 1,
+            tick: 0,
+            error: None,
+        }
+    }
+
+    /// Only write every `every` ticks.
+    pub fn every(mut self, every: u64) -> Self {
+        self.every = every.max(1);
+        self
+    }
+
+    /// Returns the writer, or the first error hit while streaming.
+    pub fn into_inner(self) -> io::Result<W> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.writer),
+        }
+    }
+
+    fn write(&mut self, output: &Output, input: &Input) -> io::Result<()> {
+        let record = Record {
+            tick: self.tick,
+            output,
+            input: Some(input),
+        };
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+impl<W> Controller for StreamingRecorder<W>
+where
+    W: Write,
+{
+    fn update(&mut self, _dt: f32, output: &Output, input: &mut Input) {
+        if self.error.is_none() && self.tick.is_multiple_of(self.every) {
+            self.error = self.write(output, input).err();
+        }
+        self.tick += 1;
+    }
+
+    fn finish(&mut self) {
+        if self.error.is_none() {
+            self.error = self.writer.flush().err();
+        }
+    }
+}
+
+/// Keeps the complete reactor state of every tick in memory.
+pub struct Recorder {
+    records: Vec<Value>,
+    with_input: bool,
+    tick: u64,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self {
+            records: Vec::new(),
+            with_input: false,
+            tick: 0,
+        }
+    }
+
+    /// Also record the input set by the controllers.
+    pub fn with_input(mut self) -> Self {
+        self.with_input = true;
+        self
+    }
+
+    pub fn records(&self) -> &[Value] {
+        &self.records
+    }
+
+    pub fn into_records(self) -> Vec<Value> {
+        self.records
+    }
+
+    /// Writes the records as JSON lines.
+    pub fn into_ndjson(self, mut writer: impl Write) -> io::Result<()> {
+        for record in &self.records {
+            serde_json::to_writer(&mut writer, record)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Controller for Recorder {
+    fn update(&mut self, _dt: f32, output: &Output, input: &mut Input) {
+        let record = Record {
+            tick: self.tick,
+            output,
+            input: self.with_input.then_some(&*input),
+        };
+        self.records
+            .push(serde_json::to_value(record).expect("reactor state serializes to JSON"));
+        self.tick += 1;
+    }
+
+    fn reset(&mut self) {
+        self.records.clear();
+        self.tick = 0;
+    }
+}
@@ -0,0 +1,587 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Output, Reactor};
+
+/// Summary statistics of a simulation run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    setpoint: f32,
+    critical_temperature: f32,
+    max_temperature: f32,
+    min_temperature: f32,
+    mean_temperature: f32,
+    /// Largest excursion above the setpoint.
+    max_overshoot: f32,
+    /// Seconds spent above the critical temperature.
+    time_unsafe: f32,
+    /// Energy delivered, the integral of power over seconds.
+    power_delivered: f32,
+    /// Energy the load asked for, the integral of load times max power.
+    energy_demanded: f32,
+    /// Fraction of ticks the voltage stayed within the voltage limits.
+    voltage_stable: f32,
+    /// Mean seconds from a load step until the power covered 90% of the step.
+    mean_tracking_lag: f32,
+    max_tracking_lag: f32,
+    /// Peak past the setpoint in percent of the step from the initial
+    /// temperature, the step responses below are `None` without a step.
+    percent_overshoot: Option<f32>,
+    /// Seconds from 10% to 90% of the step.
+    rise_time: Option<f32>,
+    /// Seconds until the temperature stays within 2% of the step around the
+    /// setpoint, `None` if it never settles.
+    settling_time: Option<f32>,
+    indices: PerformanceIndices,
+    /// Durability burnt by fission, inserted rods are counted separately.
+    fuel_consumed: f32,
+    /// Durability added by inserted fuel rods.
+    #[serde(default)]
+    fuel_inserted: f32,
+}
+
+impl RunReport {
+    pub fn get_setpoint(&self) -> f32 {
+        self.setpoint
+    }
+
+    pub fn get_critical_temperature(&self) -> f32 {
+        self.critical_temperature
+    }
+
+    pub fn get_max_temperature(&self) -> f32 {
+        self.max_temperature
+    }
+
+    pub fn get_min_temperature(&self) -> f32 {
+        self.min_temperature
+    }
+
+    pub fn get_mean_temperature(&self) -> f32 {
+        self.mean_temperature
+    }
+
+    pub fn get_max_overshoot(&self) -> f32 {
+        self.max_overshoot
+    }
+
+    pub fn get_time_unsafe(&self) -> f32 {
+        self.time_unsafe
+    }
+
+    pub fn get_power_delivered(&self) -> f32 {
+        self.power_delivered
+    }
+
+    pub fn get_energy_demanded(&self) -> f32 {
+        self.energy_demanded
+    }
+
+    pub fn get_voltage_stable(&self) -> f32 {
+        self.voltage_stable
+    }
+
+    pub fn get_mean_tracking_lag(&self) -> f32 {
+        self.mean_tracking_lag
+    }
+
+    pub fn get_max_tracking_lag(&self) -> f32 {
+        self.max_tracking_lag
+    }
+
+    pub fn get_percent_overshoot(&self) -> Option<f32> {
+        self.percent_overshoot
+    }
+
+    pub fn get_rise_time(&self) -> Option<f32> {
+        self.rise_time
+    }
+
+    pub fn get_settling_time(&self) -> Option<f32> {
+        self.settling_time
+    }
+
+    pub fn get_performance_indices(&self) -> PerformanceIndices {
+        self.indices
+    }
+
+    pub fn get_fuel_consumed(&self) -> f32 {
+        self.fuel_consumed
+    }
+
+    pub fn get_fuel_inserted(&self) -> f32 {
+        self.fuel_inserted
+    }
+
+    pub fn is_safe(&self) -> bool {
+        self.max_temperature <= self.critical_temperature
+    }
+
+    /// How the metrics changed going from this report to `other`.
+    pub fn compare(&self, other: &RunReport) -> ReportDiff {
+        let mut metrics = vec![
+            MetricDiff::new(
+                "max_temperature",
+                self.max_temperature,
+                other.max_temperature,
+            ),
+            MetricDiff::new("max_overshoot", self.max_overshoot, other.max_overshoot),
+            MetricDiff::new("time_unsafe", self.time_unsafe, other.time_unsafe),
+            MetricDiff::new("iae", self.indices.iae, other.indices.iae),
+            MetricDiff::new("ise", self.indices.ise, other.indices.ise),
+            MetricDiff::new("itae", self.indices.itae, other.indices.itae),
+            MetricDiff::new(
+                "power_delivered",
+                self.power_delivered,
+                other.power_delivered,
+            ),
+            MetricDiff::new("voltage_stable", self.voltage_stable, other.voltage_stable),
+            MetricDiff::new(
+                "mean_tracking_lag",
+                self.mean_tracking_lag,
+                other.mean_tracking_lag,
+            ),
+            MetricDiff::new("fuel_consumed", self.fuel_consumed, other.fuel_consumed),
+        ];
+        let optional = [
+            (
+                "percent_overshoot",
+                self.percent_overshoot,
+                other.percent_overshoot,
+            ),
+            ("rise_time", self.rise_time, other.rise_time),
+            ("settling_time", self.settling_time, other.settling_time),
+        ];
+        for (name, before, after) in optional {
+            if let Some((before, after)) = before.zip(after) {
+                metrics.push(MetricDiff::new(name, before, after));
+            }
+        }
+        ReportDiff { metrics }
+    }
+}
+
+/// A metric of two `RunReport`s.
+#[derive(Debug, Clone, Copy)]
+pub struct MetricDiff {
+    name: &'static str,
+    before: f32,
+    after: f32,
+}
+
+impl MetricDiff {
+    fn new(name: &'static str, before: f32, after: f32) -> Self {
+        Self {
+            name,
+            before,
+            after,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn get_before(&self) -> f32 {
+        self.before
+    }
+
+    pub fn get_after(&self) -> f32 {
+        self.after
+    }
+
+    /// After minus before.
+    pub fn get_delta(&self) -> f32 {
+        self.after - self.before
+    }
+}
+
+/// Result of `RunReport::compare`, prints as a table. Step response metrics
+/// are left out unless both reports have them.
+#[derive(Debug, Clone)]
+pub struct ReportDiff {
+    metrics: Vec<MetricDiff>,
+}
+
+impl ReportDiff {
+    pub fn metrics(&self) -> &[MetricDiff] {
+        &self.metrics
+    }
+
+    pub fn get(&self, name: &str) -> Option<&MetricDiff> {
+        self.metrics.iter().find(|metric| metric.name == name)
+    }
+}
+
+impl Display for ReportDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<20} {:>14} {:>14} {:>14}",
+            "metric", "before", "after", "delta"
+        )?;
+        for metric in &self.metrics {
+            writeln!(
+                f,
+                "{:<20} {:>14.3} {:>14.3} {:>+14.3}",
+                metric.name,
+                metric.before,
+                metric.after,
+                metric.get_delta()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Integrals of the temperature error, the usual indices to rank controllers
+/// by. Lower is better.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PerformanceIndices {
+    /// Integral of the absolute error.
+    iae: f32,
+    /// Integral of the squared error.
+    ise: f32,
+    /// Integral of the time weighted absolute error.
+    itae: f32,
+}
+
+impl PerformanceIndices {
+    /// Indices of `temperatures` sampled every `dt` seconds against `setpoint`.
+    pub fn from_temperatures(temperatures: &[f32], dt: f32, setpoint: f32) -> Self {
+        let mut indices = Self::zero();
+        for (tick, temperature) in temperatures.iter().enumerate() {
+            indices.add(temperature - setpoint, tick as f32 * dt, dt);
+        }
+        indices
+    }
+
+    fn zero() -> Self {
+        Self {
+            iae: 0.0,
+            ise: 0.0,
+            itae: 0.0,
+        }
+    }
+
+    /// Adds the `error` of a tick `dt` seconds long starting at `time`.
+    fn add(&mut self, error: f32, time: f32, dt: f32) {
+        let error = error.abs();
+        self.iae += error * dt;
+        self.ise += error * error * dt;
+        self.itae += time * error * dt;
+    }
+
+    pub fn get_iae(&self) -> f32 {
+        self.iae
+    }
+
+    pub fn get_ise(&self) -> f32 {
+        self.ise
+    }
+
+    pub fn get_itae(&self) -> f32 {
+        self.itae
+    }
+}
+
+/// Half width of the settling band, as a fraction of the step.
+const SETTLING_BAND: f32 = 0.02;
+
+/// Smallest change of the load within a tick, in percent, counted as a step.
+const LOAD_STEP: f32 = 5.0;
+
+/// A load step the power has not caught up with yet.
+struct LoadStep {
+    time: f32,
+    start_power: f32,
+    target_power: f32,
+}
+
+/// Accumulates a `RunReport` tick by tick.
+pub(crate) struct ReportBuilder {
+    setpoint: f32,
+    critical_temperature: f32,
+    ticks: u64,
+    max_temperature: f32,
+    min_temperature: f32,
+    temperature_sum: f64,
+    time_unsafe: f32,
+    power_delivered: f32,
+    power_max: f32,
+    energy_demanded: f32,
+    voltage_limits: (f32, f32),
+    voltage_stable_ticks: u64,
+    time: f32,
+    previous_load: Option<f32>,
+    load_step: Option<LoadStep>,
+    tracking_lags: u64,
+    tracking_lag_sum: f32,
+    max_tracking_lag: f32,
+    indices: PerformanceIndices,
+    initial_temperature: Option<f32>,
+    /// Furthest progress towards the setpoint, 1.0 is at the setpoint.
+    max_progress: f32,
+    rise_start: Option<f32>,
+    rise_end: Option<f32>,
+    last_unsettled: Option<f32>,
+    settled: bool,
+    last_fuel: Option<f32>,
+    fuel_consumed: f32,
+    fuel_inserted: f32,
+}
+
+impl ReportBuilder {
+    /// Judges the run against the limits of `reactor`.
+    pub(crate) fn new(setpoint: f32, reactor: &Reactor) -> Self {
+        Self {
+            setpoint,
+            critical_temperature: reactor.get_safety_limits().get_critical_temperature(),
+            ticks: 0,
+            max_temperature: f32::NEG_INFINITY,
+            min_temperature: f32::INFINITY,
+            temperature_sum: 0.0,
+            time_unsafe: 0.0,
+            power_delivered: 0.0,
+            power_max: reactor.get_power_max(),
+            energy_demanded: 0.0,
+            voltage_limits: reactor.get_voltage_limits(),
+            voltage_stable_ticks: 0,
+            time: 0.0,
+            previous_load: None,
+            load_step: None,
+            tracking_lags: 0,
+            tracking_lag_sum: 0.0,
+            max_tracking_lag: 0.0,
+            indices: PerformanceIndices::zero(),
+            initial_temperature: None,
+            max_progress: f32::NEG_INFINITY,
+            rise_start: None,
+            rise_end: None,
+            last_unsettled: None,
+            settled: false,
+            last_fuel: None,
+            fuel_consumed: 0.0,
+            fuel_inserted: 0.0,
+        }
+    }
+
+    pub(crate) fn record(&mut self, dt: f32, output: &Output) {
+        let temperature = output.get_temperature();
+        self.ticks += 1;
+        self.max_temperature = self.max_temperature.max(temperature);
+        self.min_temperature = self.min_temperature.min(temperature);
+        self.temperature_sum += temperature as f64;
+        if temperature > self.critical_temperature {
+            self.time_unsafe += dt;
+        }
+        self.power_delivered += output.get_power() * dt;
+        self.energy_demanded += output.get_load() / 100.0 * self.power_max * dt;
+        let (undervolt, overvolt) = self.voltage_limits;
+        if (undervolt..=overvolt).contains(&output.get_voltage()) {
+            self.voltage_stable_ticks += 1;
+        }
+        self.indices.add(temperature - self.setpoint, self.time, dt);
+        self.time += dt;
+        self.record_tracking(output);
+        self.record_step_response(temperature);
+        self.record_fuel(output.get_fuel_remaining());
+    }
+
+    /// A rise in the fuel remaining is an inserted rod, it doesn't offset the
+    /// fuel consumed.
+    fn record_fuel(&mut self, fuel: f32) {
+        if let Some(last) = self.last_fuel.replace(fuel) {
+            if fuel > last {
+                self.fuel_inserted += fuel - last;
+            } else {
+                self.fuel_consumed += last - fuel;
+            }
+        }
+    }
+
+    /// Starts timing on every load step and stops once the power covered 90%
+    /// of the step. A step interrupted by the next one is not counted.
+    fn record_tracking(&mut self, output: &Output) {
+        let load = output.get_load();
+        let power = output.get_power();
+        let stepped = self
+            .previous_load
+            .is_some_and(|previous| (load - previous).abs() >= LOAD_STEP);
+        self.previous_load = Some(load);
+
+        if stepped {
+            self.load_step = Some(LoadStep {
+                time: self.time,
+                start_power: power,
+                target_power: load / 100.0 * self.power_max,
+            });
+        } else if let Some(step) = &self.load_step {
+            let remaining = (step.target_power - power).abs();
+            if remaining <= 0.1 * (step.target_power - step.start_power).abs() {
+                let lag = self.time - step.time;
+                self.tracking_lags += 1;
+                self.tracking_lag_sum += lag;
+                self.max_tracking_lag = self.max_tracking_lag.max(lag);
+                self.load_step = None;
+            }
+        }
+    }
+
+    /// Treats the run as a step from the first temperature to the setpoint.
+    fn record_step_response(&mut self, temperature: f32) {
+        let initial = *self.initial_temperature.get_or_insert(temperature);
+        let step = self.setpoint - initial;
+        if step.abs() <= f32::EPSILON {
+            return;
+        }
+
+        let progress = (temperature - initial) / step;
+        self.max_progress = self.max_progress.max(progress);
+        if progress >= 0.1 && self.rise_start.is_none() {
+            self.rise_start = Some(self.time);
+        }
+        if progress >= 0.9 && self.rise_end.is_none() {
+            self.rise_end = Some(self.time);
+        }
+        self.settled = (progress - 1.0).abs() <= SETTLING_BAND;
+        if !self.settled {
+            self.last_unsettled = Some(self.time);
+        }
+    }
+
+    pub(crate) fn build(self) -> RunReport {
+        let has_step = self.max_progress.is_finite();
+        let percent_overshoot = has_step.then(|| (self.max_progress - 1.0).max(0.0) * 100.0);
+        let rise_time = self
+            .rise_start
+            .zip(self.rise_end)
+            .map(|(start, end)| end - start);
+        let settling_time = self
+            .settled
+            .then(|| self.last_unsettled.unwrap_or_default());
+
+        if self.ticks == 0 {
+            return RunReport {
+                setpoint: self.setpoint,
+                critical_temperature: self.critical_temperature,
+                max_temperature: 0.0,
+                min_temperature: 0.0,
+                mean_temperature: 0.0,
+                max_overshoot: 0.0,
+                time_unsafe: 0.0,
+                power_delivered: 0.0,
+                energy_demanded: 0.0,
+                voltage_stable: 0.0,
+                mean_tracking_lag: 0.0,
+                max_tracking_lag: 0.0,
+                percent_overshoot: None,
+                rise_time: None,
+                settling_time: None,
+                indices: PerformanceIndices::zero(),
+                fuel_consumed: 0.0,
+                fuel_inserted: 0.0,
+            };
+        }
+
+        RunReport {
+            setpoint: self.setpoint,
+            critical_temperature: self.critical_temperature,
+            max_temperature: self.max_temperature,
+            min_temperature: self.min_temperature,
+            mean_temperature: (self.temperature_sum / self.ticks as f64) as f32,
+            max_overshoot: (self.max_temperature - self.setpoint).max(0.0),
+            time_unsafe: self.time_unsafe,
+            power_delivered: self.power_delivered,
+            energy_demanded: self.energy_demanded,
+            voltage_stable: self.voltage_stable_ticks as f32 / self.ticks as f32,
+            mean_tracking_lag: if self.tracking_lags > 0 {
+                self.tracking_lag_sum / self.tracking_lags as f32
+            } else {
+                0.0
+            },
+            max_tracking_lag: self.max_tracking_lag,
+            percent_overshoot,
+            rise_time,
+            settling_time,
+            indices: self.indices,
+            fuel_consumed: self.fuel_consumed,
+            fuel_inserted: self.fuel_inserted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        FuelManagerController, FuelRod, Load, LoadProfile, Simulation, ThresholdController,
+    };
+
+    fn run_with_load(profile: LoadProfile) -> RunReport {
+        let controller = (
+            Load::with_profile(profile),
+            ThresholdController::new(5100.0, 4900.0, 100.0, 0.0),
+        );
+        let reactor = Reactor::builder()
+            .fuel_potential(320.0)
+            .auto_turbine(true)
+            .build();
+        Simulation::new(Duration::from_secs(60), reactor, controller)
+            .run_with_report()
+            .1
+    }
+
+    #[test]
+    fn underdamped_step_response() {
+        let mut builder = ReportBuilder::new(5000.0, &Reactor::new(320.0, 4000.0));
+        let temperatures = [
+            0.0, 1000.0, 3000.0, 5000.0, 6000.0, 5500.0, 4800.0, 5050.0, 5000.0, 4950.0,
+        ];
+        for temperature in temperatures {
+            builder.record(1.0, &Output::for_test(temperature, 0.0, 0.0));
+        }
+        let report = builder.build();
+        assert!((report.get_percent_overshoot().unwrap() - 20.0).abs() < 1e-3);
+        // 10% reached at 2 s and 90% at 4 s
+        assert_eq!(report.get_rise_time(), Some(2.0));
+        // Last outside the 2% band at 4800
+        assert_eq!(report.get_settling_time(), Some(7.0));
+    }
+
+    #[test]
+    fn load_profile_sets_the_demand() {
+        let report = run_with_load(LoadProfile::Step { at: 0.0, to: 50.0 });
+        let demanded = 0.5 * 4000.0 * 60.0;
+        assert!((report.get_energy_demanded() - demanded).abs() < 0.01 * demanded);
+        assert!(report.get_power_delivered() > 0.0);
+        assert!(report.get_voltage_stable() > 0.0 && report.get_voltage_stable() < 1.0);
+    }
+
+    #[test]
+    fn inserted_fuel_is_not_consumed() {
+        let reactor = Reactor::builder()
+            .fuel_rods(vec![FuelRod::new(320.0, 10.0)])
+            .build();
+        let controller = (
+            ThresholdController::new(5100.0, 4900.0, 100.0, 0.0),
+            FuelManagerController::new(9.5, FuelRod::new(320.0, 100.0)),
+        );
+        let (_, report) =
+            Simulation::new(Duration::from_secs(60), reactor, controller).run_with_report();
+        assert!(report.get_fuel_inserted() > 85.0);
+        assert!(report.get_fuel_consumed() > 0.0);
+        assert!(report.get_fuel_consumed() < 6.0);
+    }
+
+    #[test]
+    fn load_step_is_tracked() {
+        let report = run_with_load(LoadProfile::Step { at: 30.0, to: 50.0 });
+        let lag = report.get_max_tracking_lag();
+        assert!(lag > 0.0 && lag < 30.0, "lag {lag}");
+        assert_eq!(report.get_mean_tracking_lag(), lag);
+    }
+}
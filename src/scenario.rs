@@ -0,0 +1,110 @@
+use std::{fs::File, io::Read, path::Path, time::Duration};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{Load, LoadProfile, PidController, Reactor, RunReport, Simulation, SETPOINT_DEFAULT};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PidGains {
+    kp: f32,
+    ki: f32,
+    kd: f32,
+}
+
+/// A whole experiment described in a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    fuel_potential: f32,
+    power_max: f32,
+    /// Duration in seconds.
+    duration: u64,
+    #[serde(default = "default_setpoint")]
+    setpoint: f32,
+    #[serde(default)]
+    load: LoadProfile,
+    /// Let the reactor match the turbine to the load, see
+    /// `Reactor::set_auto_turbine`.
+    #[serde(default)]
+    auto_turbine: bool,
+    pid: PidGains,
+}
+
+fn default_setpoint() -> f32 {
+    SETPOINT_DEFAULT
+}
+
+impl Scenario {
+    /// Reads a scenario from JSON.
+    pub fn from_reader(reader: impl Read) -> serde_json::Result<Self> {
+        serde_json::from_reader(reader)
+    }
+
+    pub fn run(&self) -> RunReport {
+        let reactor = Reactor::builder()
+            .fuel_potential(self.fuel_potential)
+            .power_max(self.power_max)
+            .auto_turbine(self.auto_turbine)
+            .build();
+        let load = Load::with_profile(self.load);
+        let pid =
+            PidController::new(self.pid.kp, self.pid.ki, self.pid.kd).with_setpoint(self.setpoint);
+
+        let (_, report) = Simulation::new(Duration::from_secs(self.duration), reactor, (load, pid))
+            .with_setpoint(self.setpoint)
+            .run_with_report();
+        report
+    }
+}
+
+/// A scenario together with the report of running it, archived as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Experiment {
+    scenario: Scenario,
+    report: RunReport,
+}
+
+impl Experiment {
+    /// Runs `scenario` and keeps its report.
+    pub fn run(scenario: Scenario) -> Self {
+        let report = scenario.run();
+        Self { scenario, report }
+    }
+
+    pub fn get_scenario(&self) -> &Scenario {
+        &self.scenario
+    }
+
+    pub fn get_report(&self) -> &RunReport {
+        &self.report
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        serde_json::to_writer_pretty(File::create(path)?, self)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_reader(File::open(path)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SafetyLimits;
+
+    #[test]
+    fn shipped_scenario_stays_safe() {
+        let scenario =
+            Scenario::from_reader(include_str!("../scenarios/square_load.json").as_bytes())
+                .unwrap();
+        let report = scenario.run();
+        let meltdown = SafetyLimits::default().get_meltdown_temperature();
+        assert!(report.get_max_temperature() < meltdown);
+        assert!(report.get_power_delivered() > 0.9 * report.get_energy_demanded());
+    }
+}
@@ -0,0 +1,108 @@
+use serde::Serialize;
+
+use crate::{Controller, Input, Output};
+
+/// Wiring outputs of the in-game reactor. Only carries what the game
+/// publishes, fission and turbine rate are not available.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ReactorSignals {
+    temperature_out: f32,
+    load_value_out: f32,
+    power_value_out: f32,
+    fuel_out: f32,
+}
+
+impl ReactorSignals {
+    pub fn temperature_out(&self) -> f32 {
+        self.temperature_out
+    }
+
+    pub fn load_value_out(&self) -> f32 {
+        self.load_value_out
+    }
+
+    pub fn power_value_out(&self) -> f32 {
+        self.power_value_out
+    }
+
+    pub fn fuel_out(&self) -> f32 {
+        self.fuel_out
+    }
+}
+
+impl Output {
+    pub fn signals(&self) -> ReactorSignals {
+        ReactorSignals {
+            temperature_out: self.get_temperature(),
+            load_value_out: self.get_load(),
+            power_value_out: self.get_power(),
+            fuel_out: self.get_fuel_potential(),
+        }
+    }
+}
+
+/// View of `Output` restricted to the readings available in game.
+#[derive(Clone, Copy)]
+pub struct VisibleOutput<'a> {
+    output: &'a Output,
+}
+
+impl VisibleOutput<'_> {
+    pub fn get_temperature(&self) -> f32 {
+        self.output.get_temperature()
+    }
+
+    pub fn get_load(&self) -> f32 {
+        self.output.get_load()
+    }
+
+    pub fn get_power(&self) -> f32 {
+        self.output.get_power()
+    }
+
+    pub fn get_fuel_potential(&self) -> f32 {
+        self.output.get_fuel_potential()
+    }
+}
+
+impl Output {
+    pub fn visible(&self) -> VisibleOutput<'_> {
+        VisibleOutput { output: self }
+    }
+}
+
+impl<'a> From<&'a Output> for VisibleOutput<'a> {
+    fn from(output: &'a Output) -> Self {
+        output.visible()
+    }
+}
+
+/// A controller that only reads what the game exposes, and so can be
+/// deployed in game. Wrap it in `Deployable` to run it in a simulation.
+pub trait VisibleController {
+    fn update(&mut self, dt: f32, output: VisibleOutput<'_>, input: &mut Input);
+
+    fn reset(&mut self) {}
+
+    fn finish(&mut self) {}
+}
+
+#[derive(Clone)]
+pub struct Deployable<C>(pub C);
+
+impl<C> Controller for Deployable<C>
+where
+    C: VisibleController,
+{
+    fn update(&mut self, dt: f32, output: &Output, input: &mut Input) {
+        self.0.update(dt, output.visible(), input);
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+
+    fn finish(&mut self) {
+        self.0.finish();
+    }
+}
@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use crate::{
+    Controller, NoiseConfig, Output, Reactor, ReportBuilder, RunReport, SensorNoise,
+    SETPOINT_DEFAULT,
+};
+
+/// Tick rate of the game.
+pub const DEFAULT_TICK_RATE: f32 = 60.0;
+
+pub struct Simulation<C> {
+    ticks: u64,
+    tick: u64,
+    warmup_ticks: u64,
+    tick_rate: f32,
+    setpoint: f32,
+    noise: Option<SensorNoise>,
+    reactor: Reactor,
+    controller: C,
+}
+
+impl<C> Simulation<C> {
+    pub fn new(duration: Duration, reactor: Reactor, controller: C) -> Self {
+        Self::with_tick_rate(duration, DEFAULT_TICK_RATE, reactor, controller)
+    }
+
+    /// Simulation updating `tick_rate` times per simulated second.
+    pub fn with_tick_rate(
+        duration: Duration,
+        tick_rate: f32,
+        reactor: Reactor,
+        controller: C,
+    ) -> Self {
+        let ticks = (duration.as_secs_f64() * tick_rate as f64).round() as u64;
+        Self {
+            ticks,
+            tick: 0,
+            warmup_ticks: 0,
+            tick_rate,
+            setpoint: SETPOINT_DEFAULT,
+            noise: None,
+            reactor,
+            controller,
+        }
+    }
+
+    /// Setpoint the `RunReport` is measured against.
+    pub fn with_setpoint(mut self, setpoint: f32) -> Self {
+        self.setpoint = setpoint;
+        self
+    }
+
+    /// Leaves the first `warmup` of the duration out of the `RunReport`, the
+    /// reactor and controller still run during it.
+    pub fn with_warmup(mut self, warmup: Duration) -> Self {
+        self.warmup_ticks = (warmup.as_secs_f64() * self.tick_rate as f64).round() as u64;
+        self
+    }
+
+    /// Adds noise to the readings the controller sees.
+    pub fn with_noise(mut self, noise: NoiseConfig) -> Self {
+        self.noise = Some(SensorNoise::new(noise));
+        self
+    }
+
+    pub fn get_ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn get_tick_rate(&self) -> f32 {
+        self.tick_rate
+    }
+
+    pub fn get_time_delta(&self) -> f32 {
+        1.0 / self.tick_rate
+    }
+
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn is_warming_up(&self) -> bool {
+        self.tick < self.warmup_ticks
+    }
+
+    pub fn get_reactor(&self) -> &Reactor {
+        &self.reactor
+    }
+
+    pub fn get_controller(&self) -> &C {
+        &self.controller
+    }
+}
+
+impl<C> Simulation<C>
+where
+    C: Controller,
+{
+    pub fn run(self) -> C {
+        self.run_with_reactor().0
+    }
+
+    /// Runs the simulation and hands back the reactor as well, so a following
+    /// simulation can continue from its state.
+    pub fn run_with_reactor(mut self) -> (C, Reactor) {
+        while self.step() {}
+        self.controller.finish();
+        (self.controller, self.reactor)
+    }
+
+    /// Runs the simulation and summarizes it in a `RunReport`.
+    pub fn run_with_report(mut self) -> (C, RunReport) {
+        let dt = self.get_time_delta();
+        let mut report = ReportBuilder::new(self.setpoint, &self.reactor);
+        while self.step() {
+            if self.tick > self.warmup_ticks {
+                report.record(dt, self.reactor.get_output());
+            }
+        }
+        self.controller.finish();
+        (self.controller, report.build())
+    }
+
+    /// Runs until `predicate` holds for the reactor output or `max_ticks`
+    /// have passed, regardless of the duration. Returns the tick the
+    /// predicate fired at.
+    pub fn run_until(
+        mut self,
+        mut predicate: impl FnMut(&Output) -> bool,
+        max_ticks: u64,
+    ) -> (C, Option<u64>) {
+        for _ in 0..max_ticks {
+            self.advance();
+            if predicate(self.reactor.get_output()) {
+                self.controller.finish();
+                return (self.controller, Some(self.tick));
+            }
+        }
+        self.controller.finish();
+        (self.controller, None)
+    }
+
+    /// Advances a single tick, returns false once the duration is exhausted.
+    pub fn step(&mut self) -> bool {
+        if self.tick >= self.ticks {
+            return false;
+        }
+
+        self.advance();
+        true
+    }
+
+    fn advance(&mut self) {
+        let dt = self.get_time_delta();
+        match &mut self.noise {
+            Some(noise) => {
+                let output = noise.apply(self.reactor.get_output());
+                self.controller.update(dt, &output, self.reactor.get_input_mut());
+            }
+            None => {
+                let (input, output) = self.reactor.controls();
+                self.controller.update(dt, output, input);
+            }
+        }
+        self.reactor.update(dt);
+        self.tick += 1;
+    }
+}
+
+impl Reactor {
+    /// Runs the reactor for `ticks` at 60 Hz and returns the output of every tick.
+    pub fn simulate(mut self, ticks: u64, mut controller: impl Controller) -> Vec<Output> {
+        let dt = 1.0 / DEFAULT_TICK_RATE;
+        let mut trace = Vec::with_capacity(ticks as usize);
+        for _ in 0..ticks {
+            let (input, output) = self.controls();
+            controller.update(dt, output, input);
+            self.update(dt);
+            trace.push(self.get_output().clone());
+        }
+        controller.finish();
+        trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Input;
+
+    /// Temperatures the controller was shown, while running it bang-bang.
+    #[derive(Default)]
+    struct Readings(Vec<f32>);
+
+    impl Controller for Readings {
+        fn update(&mut self, _dt: f32, output: &Output, input: &mut Input) {
+            self.0.push(output.get_temperature());
+            let on = output.get_temperature() < SETPOINT_DEFAULT;
+            input.set_fission_rate(if on { 100.0 } else { 0.0 });
+            input.set_turbine_rate(50.0);
+        }
+    }
+
+    fn noisy_run(seed: u64) -> Vec<f32> {
+        Simulation::new(
+            Duration::from_secs(30),
+            Reactor::new(320.0, 4000.0),
+            Readings::default(),
+        )
+        .with_noise(NoiseConfig::new(50.0, seed))
+        .run()
+        .0
+    }
+
+    #[test]
+    fn fractional_duration_keeps_remainder() {
+        let simulation =
+            Simulation::new(Duration::from_millis(500), Reactor::new(320.0, 4000.0), ());
+        assert_eq!(simulation.get_ticks(), 30);
+        let simulation =
+            Simulation::new(Duration::from_millis(1500), Reactor::new(320.0, 4000.0), ());
+        assert_eq!(simulation.get_ticks(), 90);
+    }
+
+    #[test]
+    fn same_noise_seed_reproduces_run() {
+        assert_eq!(noisy_run(7), noisy_run(7));
+        assert_ne!(noisy_run(7), noisy_run(8));
+    }
+}
@@ -0,0 +1,131 @@
+use std::fmt::{Display, Write};
+
+use serde::Serialize;
+
+use crate::Reactor;
+
+/// Temperatures range from 0 up to this.
+pub const MAX_TEMPERATURE: f32 = 10000.0;
+
+/// Temperature controllers aim for and reports measure overshoot against by
+/// default.
+pub const SETPOINT_DEFAULT: f32 = 5000.0;
+
+/// Temperature above which the reactor is considered unsafe.
+pub const UNSAFE_TEMPERATURE: f32 = 6482.0;
+
+/// Staying above this temperature melts the reactor down.
+pub const MELTDOWN_TEMPERATURE: f32 = 7000.0;
+
+/// Temperatures the reactor is judged against, defaulting to the game's.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SafetyLimits {
+    critical_temperature: f32,
+    meltdown_temperature: f32,
+}
+
+impl SafetyLimits {
+    pub fn new(critical_temperature: f32, meltdown_temperature: f32) -> Self {
+        Self {
+            critical_temperature,
+            meltdown_temperature,
+        }
+    }
+
+    /// Above this the reactor is unsafe.
+    pub fn get_critical_temperature(&self) -> f32 {
+        self.critical_temperature
+    }
+
+    /// Staying above this melts the reactor down.
+    pub fn get_meltdown_temperature(&self) -> f32 {
+        self.meltdown_temperature
+    }
+
+    pub(crate) fn set_meltdown_temperature(&mut self, meltdown_temperature: f32) {
+        self.meltdown_temperature = meltdown_temperature;
+    }
+}
+
+impl Default for SafetyLimits {
+    fn default() -> Self {
+        Self::new(UNSAFE_TEMPERATURE, MELTDOWN_TEMPERATURE)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ReactorStatus {
+    Nominal,
+    Overheating,
+    Meltdown,
+    Tripped,
+    Scrammed,
+}
+
+impl ReactorStatus {
+    pub const ALL: [ReactorStatus; 5] = [
+        ReactorStatus::Nominal,
+        ReactorStatus::Overheating,
+        ReactorStatus::Meltdown,
+        ReactorStatus::Tripped,
+        ReactorStatus::Scrammed,
+    ];
+}
+
+impl Display for ReactorStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ReactorStatus::Nominal => "Nominal",
+            ReactorStatus::Overheating => "Overheating",
+            ReactorStatus::Meltdown => "Meltdown",
+            ReactorStatus::Tripped => "Tripped",
+            ReactorStatus::Scrammed => "Scrammed",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Transitions implemented by `Reactor::status`, as (from, to, trigger).
+const TRANSITIONS: &[(ReactorStatus, ReactorStatus, &str)] = &[
+    (
+        ReactorStatus::Nominal,
+        ReactorStatus::Overheating,
+        "temperature > critical temperature",
+    ),
+    (
+        ReactorStatus::Overheating,
+        ReactorStatus::Nominal,
+        "temperature <= critical temperature",
+    ),
+    (
+        ReactorStatus::Overheating,
+        ReactorStatus::Meltdown,
+        "temperature > meltdown temperature for meltdown delay",
+    ),
+];
+
+impl Reactor {
+    pub fn status(&self) -> ReactorStatus {
+        if self.is_melted_down() {
+            ReactorStatus::Meltdown
+        } else if !self.is_safe() {
+            ReactorStatus::Overheating
+        } else {
+            ReactorStatus::Nominal
+        }
+    }
+
+    /// Graphviz dot of the status transitions and their triggers.
+    /// States without transitions are not reachable yet.
+    pub fn state_diagram() -> String {
+        let mut dot = String::from("digraph reactor {\n");
+        for status in ReactorStatus::ALL {
+            writeln!(dot, "    {status};").unwrap();
+        }
+        for (from, to, trigger) in TRANSITIONS {
+            writeln!(dot, "    {from} -> {to} [label=\"{trigger}\"];").unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}